@@ -22,6 +22,26 @@ pub struct BasicMotion<
 
     drive_controller: D,
     turn_controller: T,
+
+    angular_priority: bool,
+    cancelled: bool,
+}
+
+impl<
+        D: MotionController<Input = f64, Output = f64>,
+        T: MotionController<Input = f64, Output = f64>,
+    > BasicMotion<D, T>
+{
+    /// Preserve turning authority when the drive and turn outputs saturate the motors together.
+    ///
+    /// By default, both outputs are scaled down equally when their sum exceeds the motors' maximum
+    /// voltage, which can leave the robot driving straight past its target heading. With angular
+    /// priority enabled, the turn output is preserved and the drive output only uses the remaining
+    /// headroom (see [`Voltages::prioritized`]).
+    pub fn with_angular_priority(mut self, angular_priority: bool) -> Self {
+        self.angular_priority = angular_priority;
+        self
+    }
 }
 
 impl<
@@ -32,19 +52,35 @@ impl<
     type Output = Voltages;
 
     fn update(&mut self, ctx: TrackingContext) -> Self::Output {
+        if self.cancelled {
+            return Voltages::default();
+        }
+
         self.drive_error = self.distance - ctx.forward_travel;
         self.turn_error = math::normalize_angle(self.angle - ctx.heading);
 
-        let drive_output = self.drive_controller.update(self.drive_error);
-        let turn_output = self.drive_controller.update(self.turn_error);
+        let drive_output = self
+            .drive_controller
+            .update(self.drive_error, Motor::DATA_READ_INTERVAL);
+        let turn_output = self
+            .turn_controller
+            .update(self.turn_error, Motor::DATA_READ_INTERVAL);
 
-        Voltages(drive_output + turn_output, drive_output - turn_output)
-            .normalized(Motor::MAX_VOLTAGE)
+        if self.angular_priority {
+            Voltages::prioritized(drive_output, turn_output, Motor::MAX_VOLTAGE)
+        } else {
+            Voltages(drive_output - turn_output, drive_output + turn_output)
+                .normalized(Motor::MAX_VOLTAGE)
+        }
     }
 
     fn is_settled(&self) -> bool {
-        self.drive_error.abs() < self.drive_tolerance && self.turn_error.abs() < self.turn_tolerance
+        self.cancelled
+            || (self.drive_error.abs() < self.drive_tolerance
+                && self.turn_error.abs() < self.turn_tolerance)
     }
 
-    fn cancel(&mut self) {}
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
 }
@@ -0,0 +1,173 @@
+use core::{fmt, ops::ControlFlow, time::Duration};
+
+use vexide::devices::smart::Motor;
+
+use crate::{commands::Command, drivetrain::Voltages, tracking::TrackingContext};
+
+/// A command that runs a closure every update.
+///
+/// This allows custom motions to be written without creating a new type implementing [`Command`].
+/// The drivetrain's background task takes care of running the closure at a fixed rate, reading
+/// tracking data, and applying its output to the motors.
+///
+/// # Control Flow
+///
+/// The closure is given the latest [`TrackingContext`] and the time elapsed since the previous update
+/// (`dt`), and returns either:
+///
+/// - [`ControlFlow::Continue`] with the voltages to command, to keep running, or
+/// - [`ControlFlow::Break`] to finish. Once the closure breaks, it is never called again, the
+///   command outputs zero voltage, and the command is considered settled.
+///
+/// A timeout can be set with [`ClosureCommand::with_timeout`], which finishes the command in the same
+/// way if the closure hasn't broken in time.
+///
+/// The drivetrain updates its command once every [`Motor::DATA_READ_INTERVAL`], so `dt` is always that
+/// interval, and the timeout is measured by counting updates.
+///
+/// # Example
+///
+/// ```
+/// let mut iterations = 0;
+///
+/// drivetrain
+///     .execute(
+///         ClosureCommand::new(move |_ctx, _dt| {
+///             iterations += 1;
+///
+///             if iterations > 100 {
+///                 ControlFlow::Break(())
+///             } else {
+///                 ControlFlow::Continue(Voltages(6.0, 6.0))
+///             }
+///         })
+///         .with_timeout(Duration::from_secs(2)),
+///     )
+///     .await;
+/// ```
+pub struct ClosureCommand<F: FnMut(TrackingContext, Duration) -> ControlFlow<(), Voltages>> {
+    closure: F,
+    timeout: Option<Duration>,
+    elapsed: Duration,
+    finished: bool,
+}
+
+impl<F: FnMut(TrackingContext, Duration) -> ControlFlow<(), Voltages>> ClosureCommand<F> {
+    /// Construct a new [`ClosureCommand`] from a closure.
+    pub fn new(closure: F) -> Self {
+        Self {
+            closure,
+            timeout: None,
+            elapsed: Duration::ZERO,
+            finished: false,
+        }
+    }
+
+    /// Finish the command after it has been running for `timeout`, even if the closure hasn't broken.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+}
+
+impl<F: FnMut(TrackingContext, Duration) -> ControlFlow<(), Voltages>> fmt::Debug
+    for ClosureCommand<F>
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ClosureCommand")
+            .field("timeout", &self.timeout)
+            .field("elapsed", &self.elapsed)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<F: FnMut(TrackingContext, Duration) -> ControlFlow<(), Voltages> + Send + 'static> Command
+    for ClosureCommand<F>
+{
+    type Output = Voltages;
+
+    fn update(&mut self, ctx: TrackingContext) -> Self::Output {
+        if self.finished {
+            return Voltages::default();
+        }
+
+        if self.timeout.is_some_and(|timeout| self.elapsed >= timeout) {
+            self.finished = true;
+            return Voltages::default();
+        }
+
+        let dt = Motor::DATA_READ_INTERVAL;
+        self.elapsed += dt;
+
+        match (self.closure)(ctx, dt) {
+            ControlFlow::Continue(voltages) => voltages,
+            ControlFlow::Break(()) => {
+                self.finished = true;
+                Voltages::default()
+            }
+        }
+    }
+
+    fn is_settled(&self) -> bool {
+        self.finished
+    }
+
+    fn cancel(&mut self) {
+        self.finished = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finishes_when_closure_breaks() {
+        let mut iterations = 0;
+        let mut command = ClosureCommand::new(move |_ctx, dt| {
+            assert_eq!(dt, Motor::DATA_READ_INTERVAL);
+            iterations += 1;
+
+            if iterations > 3 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(Voltages(6.0, 6.0))
+            }
+        });
+
+        for _ in 0..3 {
+            assert_eq!(
+                command.update(TrackingContext::default()),
+                Voltages(6.0, 6.0)
+            );
+            assert!(!command.is_settled());
+        }
+
+        assert_eq!(
+            command.update(TrackingContext::default()),
+            Voltages(0.0, 0.0)
+        );
+        assert!(command.is_settled());
+    }
+
+    #[test]
+    fn finishes_after_timeout() {
+        let mut command =
+            ClosureCommand::new(|_ctx, _dt| ControlFlow::Continue(Voltages(6.0, 6.0)))
+                .with_timeout(Motor::DATA_READ_INTERVAL * 5);
+
+        for _ in 0..5 {
+            assert_eq!(
+                command.update(TrackingContext::default()),
+                Voltages(6.0, 6.0)
+            );
+        }
+
+        assert_eq!(
+            command.update(TrackingContext::default()),
+            Voltages(0.0, 0.0)
+        );
+        assert!(command.is_settled());
+    }
+}
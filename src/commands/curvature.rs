@@ -0,0 +1,152 @@
+use alloc::vec::Vec;
+use num_traits::real::Real;
+use vexide::devices::smart::Motor;
+
+use crate::{commands::Command, drivetrain::Voltages, tracking::TrackingContext};
+
+/// A single segment of constant curvature.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct ArcSegment {
+    /// The distance traveled along this segment.
+    pub arc_length: f64,
+
+    /// The curvature (inverse of the turning radius) of this segment.
+    ///
+    /// Positive curvature turns counterclockwise (left), negative curvature turns clockwise (right),
+    /// and zero curvature drives in a straight line.
+    pub curvature: f64,
+}
+
+impl ArcSegment {
+    /// Construct a new [`ArcSegment`] from an arc length and curvature.
+    pub const fn new(arc_length: f64, curvature: f64) -> Self {
+        Self {
+            arc_length,
+            curvature,
+        }
+    }
+}
+
+/// Drives a sequence of constant-curvature arcs.
+///
+/// Rather than generating a full path, `CurvatureDrive` commands the drivetrain to trace each
+/// [`ArcSegment`] in order, moving on to the next segment once the measured forward travel has
+/// covered the current segment's arc length.
+///
+/// # Arc Math
+///
+/// For a differential drivetrain with a track width `w` driving at a speed `v` along an arc with
+/// curvature `k`, the inside and outside wheels trace concentric arcs with radii `1/k - w/2` and
+/// `1/k + w/2`. Scaling `v` by the ratio of those radii to the center radius `1/k` gives:
+///
+/// - Left wheel: `v * (1 - k * w / 2)`
+/// - Right wheel: `v * (1 + k * w / 2)`
+///
+/// A zero-curvature segment therefore produces equal wheel speeds.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct CurvatureDrive {
+    arcs: Vec<ArcSegment>,
+    track_width: f64,
+    voltage: f64,
+
+    start_travel: Option<f64>,
+    current_arc: usize,
+}
+
+impl CurvatureDrive {
+    /// Construct a [`CurvatureDrive`] that follows `arcs` at a base `voltage`.
+    ///
+    /// `track_width` is the distance between the left and right wheels, in the same units as the
+    /// segments' arc lengths.
+    pub fn follow_arcs(arcs: impl Into<Vec<ArcSegment>>, track_width: f64, voltage: f64) -> Self {
+        Self {
+            arcs: arcs.into(),
+            track_width,
+            voltage,
+            ..Default::default()
+        }
+    }
+
+    /// Compute left and right wheel voltages for driving an arc with a given `curvature`.
+    pub fn wheel_voltages(&self, curvature: f64) -> Voltages {
+        let half_track = curvature * self.track_width / 2.0;
+
+        Voltages(
+            self.voltage * (1.0 - half_track),
+            self.voltage * (1.0 + half_track),
+        )
+        .normalized(Motor::MAX_VOLTAGE)
+    }
+}
+
+impl Command for CurvatureDrive {
+    type Output = Voltages;
+
+    fn update(&mut self, ctx: TrackingContext) -> Self::Output {
+        let start_travel = *self.start_travel.get_or_insert(ctx.forward_travel);
+        let traveled = (ctx.forward_travel - start_travel).abs();
+
+        // Advance to whichever segment our measured travel currently falls within.
+        let mut segment_end = 0.0;
+        self.current_arc = self.arcs.len();
+
+        for (i, arc) in self.arcs.iter().enumerate() {
+            segment_end += arc.arc_length.abs();
+
+            if traveled < segment_end {
+                self.current_arc = i;
+                break;
+            }
+        }
+
+        match self.arcs.get(self.current_arc) {
+            Some(arc) => self.wheel_voltages(arc.curvature),
+            None => Voltages::default(),
+        }
+    }
+
+    fn is_settled(&self) -> bool {
+        self.current_arc >= self.arcs.len()
+    }
+
+    fn cancel(&mut self) {
+        self.arcs.clear();
+        self.current_arc = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_curvature_drives_straight() {
+        let drive = CurvatureDrive::follow_arcs([ArcSegment::new(24.0, 0.0)], 12.0, 6.0);
+
+        assert_eq!(drive.wheel_voltages(0.0), Voltages(6.0, 6.0));
+
+        // Positive curvature turns counterclockwise, so the right side drives faster.
+        let Voltages(left, right) = drive.wheel_voltages(0.1);
+        assert!(right > left);
+    }
+
+    #[test]
+    fn advances_through_segments() {
+        let mut drive = CurvatureDrive::follow_arcs(
+            [ArcSegment::new(10.0, 0.0), ArcSegment::new(10.0, 0.1)],
+            12.0,
+            6.0,
+        );
+        let at = |forward_travel| TrackingContext {
+            forward_travel,
+            ..Default::default()
+        };
+
+        assert_eq!(drive.update(at(0.0)), drive.wheel_voltages(0.0));
+        assert_eq!(drive.update(at(15.0)), drive.wheel_voltages(0.1));
+        assert!(!drive.is_settled());
+
+        assert_eq!(drive.update(at(20.0)), Voltages::default());
+        assert!(drive.is_settled());
+    }
+}
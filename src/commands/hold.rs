@@ -0,0 +1,136 @@
+use vexide::devices::smart::Motor;
+
+use crate::{
+    commands::Command,
+    controller::MotionController,
+    drivetrain::Voltages,
+    math::{self, Vec2},
+    tracking::TrackingContext,
+};
+
+/// Actively holds the robot at the pose it was in when the command started.
+///
+/// The pose is captured on the first update. Afterwards, a linear controller corrects for
+/// displacement along the robot's heading and an angular controller corrects for changes in
+/// heading, allowing the robot to resist being pushed.
+///
+/// This command never settles on its own, and is intended to be run for a fixed amount of
+/// time through [`DifferentialDrivetrain::hold_pose`](crate::drivetrain::DifferentialDrivetrain::hold_pose).
+/// Once cancelled, it stops holding and outputs zero voltage.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct HoldPose<
+    L: MotionController<Input = f64, Output = f64>,
+    A: MotionController<Input = f64, Output = f64>,
+> {
+    target: Option<(Vec2, f64)>,
+    cancelled: bool,
+
+    linear_controller: L,
+    angular_controller: A,
+}
+
+impl<
+        L: MotionController<Input = f64, Output = f64>,
+        A: MotionController<Input = f64, Output = f64>,
+    > HoldPose<L, A>
+{
+    /// Construct a new [`HoldPose`] command from a linear and angular controller.
+    pub fn new(linear_controller: L, angular_controller: A) -> Self {
+        Self {
+            target: None,
+            cancelled: false,
+            linear_controller,
+            angular_controller,
+        }
+    }
+}
+
+impl<
+        L: MotionController<Input = f64, Output = f64>,
+        A: MotionController<Input = f64, Output = f64>,
+    > Command for HoldPose<L, A>
+{
+    type Output = Voltages;
+
+    fn update(&mut self, ctx: TrackingContext) -> Self::Output {
+        if self.cancelled {
+            return Voltages::default();
+        }
+
+        let (target_position, target_heading) =
+            *self.target.get_or_insert((ctx.position, ctx.heading));
+
+        // Only displacement along our current heading can be corrected by driving forwards or backwards.
+        let linear_error = (target_position - ctx.position).dot(Vec2::from_polar(1.0, ctx.heading));
+        let angular_error = math::normalize_angle(target_heading - ctx.heading);
+
+        let linear_output = self
+            .linear_controller
+            .update(linear_error, Motor::DATA_READ_INTERVAL);
+        let angular_output = self
+            .angular_controller
+            .update(angular_error, Motor::DATA_READ_INTERVAL);
+
+        Voltages(
+            linear_output - angular_output,
+            linear_output + angular_output,
+        )
+        .normalized(Motor::MAX_VOLTAGE)
+    }
+
+    fn is_settled(&self) -> bool {
+        false
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::pid::PIDController;
+
+    #[test]
+    fn corrects_displacement_from_captured_pose() {
+        let mut hold = HoldPose::new(
+            PIDController::new((1.0, 0.0, 0.0), 0.0),
+            PIDController::new((10.0, 0.0, 0.0), 0.0),
+        );
+
+        // Capture the pose at the origin.
+        assert_eq!(hold.update(TrackingContext::default()), Voltages(0.0, 0.0));
+
+        // Pushed backwards, so both sides should drive forwards equally.
+        let Voltages(left, right) = hold.update(TrackingContext {
+            position: Vec2::new(-1.0, 0.0),
+            ..Default::default()
+        });
+        assert!(left > 0.0 && left == right);
+
+        // Pushed clockwise, so the robot should turn counterclockwise.
+        let Voltages(left, right) = hold.update(TrackingContext {
+            heading: -0.1,
+            ..Default::default()
+        });
+        assert!(right > 0.0 && left == -right);
+    }
+
+    #[test]
+    fn outputs_zero_after_cancel() {
+        let mut hold = HoldPose::new(
+            PIDController::new((1.0, 0.0, 0.0), 0.0),
+            PIDController::new((1.0, 0.0, 0.0), 0.0),
+        );
+
+        hold.update(TrackingContext::default());
+        hold.cancel();
+
+        let displaced = TrackingContext {
+            position: Vec2::new(-1.0, 0.0),
+            ..Default::default()
+        };
+        assert_eq!(hold.update(displaced), Voltages(0.0, 0.0));
+    }
+}
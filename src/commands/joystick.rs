@@ -1,6 +1,7 @@
+use core::time::Duration;
 use vexide::devices::{controller::Controller, controller::ControllerError, smart::Motor};
 
-use crate::drivetrain::Voltages;
+use crate::{controller::MotionController, drivetrain::Voltages, math};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum JoystickLayout {
@@ -16,14 +17,136 @@ pub trait JoystickCommands {
 
 impl JoystickCommands for Controller {
     fn command(&self, layout: JoystickLayout) -> Result<Voltages, ControllerError> {
-        let (left_voltage, right_voltage) = match layout {
-            JoystickLayout::Tank => (
+        let voltages = match layout {
+            JoystickLayout::Tank => Voltages(
                 Motor::MAX_VOLTAGE * self.left_stick.y()? as f64,
                 Motor::MAX_VOLTAGE * self.right_stick.y()? as f64,
             ),
-            _ => todo!(),
+            JoystickLayout::SplitArcade => Voltages::from_arcade(
+                Motor::MAX_VOLTAGE * self.left_stick.y()? as f64,
+                Motor::MAX_VOLTAGE * self.right_stick.x()? as f64,
+            ),
+            JoystickLayout::LeftArcade => Voltages::from_arcade(
+                Motor::MAX_VOLTAGE * self.left_stick.y()? as f64,
+                Motor::MAX_VOLTAGE * self.left_stick.x()? as f64,
+            ),
+            JoystickLayout::RightArcade => Voltages::from_arcade(
+                Motor::MAX_VOLTAGE * self.right_stick.y()? as f64,
+                Motor::MAX_VOLTAGE * self.right_stick.x()? as f64,
+            ),
         };
 
-        Ok(Voltages(left_voltage, right_voltage))
+        Ok(voltages.normalized(Motor::MAX_VOLTAGE))
+    }
+}
+
+/// A driver assist that drives forwards and backwards while automatically holding a heading.
+///
+/// The linear output is passed straight through from a throttle input (such as a joystick axis), while
+/// an angular controller corrects for any error between the robot's measured heading and a target heading.
+///
+/// If no target heading has been set, the robot's heading is captured on the next update and held from
+/// then on. Calling [`HeadingHold::release`] clears the target, so the heading will be recaptured on the
+/// following update (for example, after the driver has finished turning manually).
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct HeadingHold<C: MotionController<Input = f64, Output = f64>> {
+    controller: C,
+    target_heading: Option<f64>,
+}
+
+impl<C: MotionController<Input = f64, Output = f64>> HeadingHold<C> {
+    /// Construct a new [`HeadingHold`] from an angular controller.
+    pub fn new(controller: C) -> Self {
+        Self {
+            controller,
+            target_heading: None,
+        }
+    }
+
+    /// Set the heading to hold, in radians.
+    pub fn set_target_heading(&mut self, heading: f64) {
+        self.target_heading = Some(heading);
+    }
+
+    /// Get the heading being held, if one has been captured or set.
+    pub fn target_heading(&self) -> Option<f64> {
+        self.target_heading
+    }
+
+    /// Stop holding the current target heading, recapturing it on the next update.
+    pub fn release(&mut self) {
+        self.target_heading = None;
+    }
+
+    /// Compute drivetrain voltages from a `throttle` input in the range [-1, 1] and the robot's
+    /// measured `heading`.
+    ///
+    /// `dt` is the time elapsed since the last update.
+    pub fn update(&mut self, throttle: f64, heading: f64, dt: Duration) -> Voltages {
+        let target_heading = *self.target_heading.get_or_insert(heading);
+
+        let linear_output = throttle * Motor::MAX_VOLTAGE;
+        let angular_output = self
+            .controller
+            .update(math::normalize_angle(target_heading - heading), dt);
+
+        Voltages(
+            linear_output - angular_output,
+            linear_output + angular_output,
+        )
+        .normalized(Motor::MAX_VOLTAGE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::PI;
+
+    use super::*;
+    use crate::controller::pid::PIDController;
+
+    const DT: Duration = Motor::DATA_READ_INTERVAL;
+
+    fn heading_hold() -> HeadingHold<PIDController> {
+        HeadingHold::new(PIDController::new((10.0, 0.0, 0.0), f64::INFINITY))
+    }
+
+    fn assert_voltages_close(actual: Voltages, expected: Voltages) {
+        assert!(
+            (actual.0 - expected.0).abs() < 1e-9 && (actual.1 - expected.1).abs() < 1e-9,
+            "expected {expected:?}, got {actual:?}"
+        );
+    }
+
+    #[test]
+    fn captures_heading_on_first_update() {
+        let mut hold = heading_hold();
+
+        assert_eq!(hold.update(0.5, 1.0, DT), Voltages(6.0, 6.0));
+        assert_eq!(hold.target_heading(), Some(1.0));
+    }
+
+    #[test]
+    fn corrects_heading_error() {
+        let mut hold = heading_hold();
+        hold.set_target_heading(0.0);
+
+        // Drifted clockwise, so the correction turns counterclockwise while the linear output passes through.
+        assert_voltages_close(hold.update(0.5, -0.1, DT), Voltages(5.0, 7.0));
+
+        // Errors are taken along the shortest arc across the ±π boundary.
+        hold.set_target_heading(PI - 0.05);
+        assert_voltages_close(hold.update(0.0, -PI + 0.05, DT), Voltages(1.0, -1.0));
+    }
+
+    #[test]
+    fn release_recaptures_heading() {
+        let mut hold = heading_hold();
+        hold.set_target_heading(0.0);
+
+        hold.release();
+        assert_eq!(hold.target_heading(), None);
+        assert_eq!(hold.update(1.0, 2.0, DT), Voltages(12.0, 12.0));
+        assert_eq!(hold.target_heading(), Some(2.0));
     }
 }
@@ -1,12 +1,21 @@
 use crate::tracking::TrackingContext;
 
 pub mod basic;
+pub mod closure;
+pub mod curvature;
+pub mod hold;
 pub mod joystick;
+pub mod timed;
 
 pub trait Command: core::fmt::Debug + Send + 'static {
     type Output;
 
     fn update(&mut self, ctx: TrackingContext) -> Self::Output;
+
+    /// Cancel the command.
+    ///
+    /// This is called when a command is abandoned before it settles. After being cancelled, a
+    /// command should produce an output that stops actuating the system (e.g. zero voltage).
     fn cancel(&mut self);
     fn is_settled(&self) -> bool;
 }
@@ -0,0 +1,92 @@
+use core::time::Duration;
+
+use vexide::devices::smart::Motor;
+
+use crate::{commands::Command, drivetrain::Voltages, tracking::TrackingContext};
+
+/// A command that drives at fixed voltages for a given duration, then stops.
+///
+/// This is an open-loop command. It ignores tracking entirely, so the distance it covers will vary with
+/// battery voltage, friction, and load.
+///
+/// The drivetrain updates its command once every [`Motor::DATA_READ_INTERVAL`], so the duration is measured
+/// by counting updates. Once `duration` has elapsed (or the command is cancelled), the command outputs zero
+/// voltage and is considered settled.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct TimedVoltages {
+    voltages: Voltages,
+    duration: Duration,
+    elapsed: Duration,
+    cancelled: bool,
+}
+
+impl TimedVoltages {
+    /// Construct a new [`TimedVoltages`] that outputs `voltages` for `duration`.
+    pub fn new(voltages: Voltages, duration: Duration) -> Self {
+        Self {
+            voltages,
+            duration,
+            elapsed: Duration::ZERO,
+            cancelled: false,
+        }
+    }
+}
+
+impl Command for TimedVoltages {
+    type Output = Voltages;
+
+    fn update(&mut self, _ctx: TrackingContext) -> Self::Output {
+        if self.is_settled() {
+            return Voltages::default();
+        }
+
+        self.elapsed += Motor::DATA_READ_INTERVAL;
+        self.voltages
+    }
+
+    fn is_settled(&self) -> bool {
+        self.cancelled || self.elapsed >= self.duration
+    }
+
+    fn cancel(&mut self) {
+        self.cancelled = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outputs_voltages_for_duration_then_zero() {
+        let mut command = TimedVoltages::new(Voltages(6.0, -3.0), Motor::DATA_READ_INTERVAL * 5);
+
+        for _ in 0..5 {
+            assert!(!command.is_settled());
+            assert_eq!(
+                command.update(TrackingContext::default()),
+                Voltages(6.0, -3.0)
+            );
+        }
+
+        assert!(command.is_settled());
+        assert_eq!(
+            command.update(TrackingContext::default()),
+            Voltages::default()
+        );
+    }
+
+    #[test]
+    fn cancel_stops_early() {
+        let mut command = TimedVoltages::new(Voltages(6.0, 6.0), Duration::from_secs(1));
+
+        command.update(TrackingContext::default());
+        command.cancel();
+
+        assert!(command.is_settled());
+        assert_eq!(
+            command.update(TrackingContext::default()),
+            Voltages::default()
+        );
+    }
+}
@@ -0,0 +1,325 @@
+use core::time::Duration;
+use num_traits::real::Real;
+
+/// A time-weighted accumulator with optional clamping and leakage.
+///
+/// Each update adds `value * dt` to a running sum, which is the same integral term used by
+/// [`PIDController`](super::pid::PIDController). This can be used as a building block for custom
+/// feedback controllers.
+///
+/// - The accumulated sum can be clamped to a maximum magnitude using [`Integrator::with_limit`], which
+///   prevents it from growing without bound while a system is saturated.
+/// - The sum can be made to decay over time using [`Integrator::with_leak`]. A leaky integrator gradually
+///   "forgets" old values, which limits the effect of past error on the current output.
+///
+/// # Resetting
+///
+/// The accumulated sum is only ever cleared by calling [`Integrator::reset`]. Limit and leak settings are
+/// kept across resets.
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub struct Integrator {
+    sum: f64,
+    limit: f64,
+    leak: f64,
+}
+
+impl Integrator {
+    /// Construct a new [`Integrator`] with no limit and no leakage.
+    pub const fn new() -> Self {
+        Self {
+            sum: 0.0,
+            limit: f64::INFINITY,
+            leak: 0.0,
+        }
+    }
+
+    /// Clamp the accumulated sum to `[-limit, limit]`.
+    ///
+    /// The sign of `limit` is ignored, and a NaN `limit` leaves the sum unbounded.
+    pub fn with_limit(mut self, limit: f64) -> Self {
+        self.limit = if limit.is_nan() {
+            f64::INFINITY
+        } else {
+            limit.abs()
+        };
+        self
+    }
+
+    /// Exponentially decay the accumulated sum at a rate of `leak` per second.
+    ///
+    /// Without any new input, the sum is multiplied by `e^(-leak * t)` after `t` seconds, so `1.0 / leak`
+    /// is the time it takes for the sum to decay to about 37% of its value. A `leak` of `0.0` disables
+    /// leakage.
+    ///
+    /// The sign of `leak` is ignored, and a `leak` that isn't finite disables leakage.
+    pub fn with_leak(mut self, leak: f64) -> Self {
+        self.leak = if leak.is_finite() { leak.abs() } else { 0.0 };
+        self
+    }
+
+    /// Accumulate `value` over the time elapsed since the last update, returning the new sum.
+    pub fn update(&mut self, value: f64, dt: Duration) -> f64 {
+        let dt = dt.as_secs_f64();
+
+        self.sum *= (-self.leak * dt).exp();
+        self.sum = (self.sum + value * dt).clamp(-self.limit, self.limit);
+
+        self.sum
+    }
+
+    /// Get the current accumulated sum.
+    pub const fn value(&self) -> f64 {
+        self.sum
+    }
+
+    /// Clear the accumulated sum.
+    pub fn reset(&mut self) {
+        self.sum = 0.0;
+    }
+}
+
+impl Default for Integrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A rate-of-change estimator with optional low-pass filtering.
+///
+/// Each update computes the difference between the given value and the previous one, divided by the time
+/// elapsed between them. Since this amplifies sensor noise, the estimate can be smoothed using
+/// [`Derivative::with_filter`].
+///
+/// # Resetting
+///
+/// The first update after construction or a call to [`Derivative::reset`] only records the given value,
+/// and returns `0.0`, since there is no previous value to compare it to. This avoids a large spike in
+/// output when the value being differentiated jumps (for example, when a controller's setpoint changes).
+/// Updates with a `dt` of zero return the previous estimate without changing any state.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct Derivative {
+    filter: f64,
+    previous_value: Option<f64>,
+    estimate: f64,
+}
+
+impl Derivative {
+    /// Construct a new, unfiltered [`Derivative`].
+    pub const fn new() -> Self {
+        Self {
+            filter: 0.0,
+            previous_value: None,
+            estimate: 0.0,
+        }
+    }
+
+    /// Smooth the estimate using an exponential moving average.
+    ///
+    /// `filter` is the weight in the range `[0.0, 1.0)` given to the previous estimate each update. A
+    /// `filter` of `0.0` disables filtering, while values closer to `1.0` give a smoother (but
+    /// slower-responding) estimate.
+    pub const fn with_filter(mut self, filter: f64) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Estimate the rate of change of `value` since the last update.
+    pub fn update(&mut self, value: f64, dt: Duration) -> f64 {
+        if dt.is_zero() {
+            return self.estimate;
+        }
+
+        if let Some(previous_value) = self.previous_value {
+            let raw = (value - previous_value) / dt.as_secs_f64();
+            self.estimate = self.filter * self.estimate + (1.0 - self.filter) * raw;
+        }
+
+        self.previous_value = Some(value);
+        self.estimate
+    }
+
+    /// Get the current rate of change estimate.
+    pub const fn value(&self) -> f64 {
+        self.estimate
+    }
+
+    /// Forget the previous value and estimate.
+    pub fn reset(&mut self) {
+        self.previous_value = None;
+        self.estimate = 0.0;
+    }
+}
+
+/// Detects stalls by comparing a commanded output against a measured velocity.
+///
+/// When a robot is pushed into a wall (or a mechanism jams), its motors will continue to receive
+/// voltage while the measured velocity stays near zero. A `StallDetector` keeps track of how long the
+/// gap between the velocity we *expect* from the commanded output and the velocity we actually *measure*
+/// has been larger than a threshold, and reports a stall once that gap has persisted for a given duration.
+///
+/// # Tuning
+///
+/// - `velocity_ratio` converts a commanded output into an expected velocity. For a drivetrain commanded in
+///   volts, this is roughly the robot's free speed divided by [`Motor::MAX_VOLTAGE`](vexide::devices::smart::Motor::MAX_VOLTAGE).
+/// - `threshold` is how far (in velocity units) the measured velocity is allowed to fall below the expected
+///   velocity before the detector starts counting.
+/// - `duration` is how long the gap must persist before a stall is reported. Setting this too short will cause
+///   the detector to trip during normal acceleration, since velocity always lags behind the commanded output.
+///
+/// > Intentionally pushing against something (for example, holding a game element against a wall or playing
+/// > defense) looks exactly like a stall to this detector. Motions that are expected to push should either not
+/// > use a `StallDetector` or use a much longer `duration`.
+#[derive(Clone, PartialEq, Debug, Copy, Default)]
+pub struct StallDetector {
+    /// Ratio between commanded output and expected velocity.
+    pub velocity_ratio: f64,
+
+    /// Allowed difference between expected and measured velocity.
+    pub threshold: f64,
+
+    /// How long the velocity gap must persist before reporting a stall.
+    pub duration: Duration,
+
+    stalled_time: Duration,
+}
+
+impl StallDetector {
+    /// Construct a new [`StallDetector`].
+    pub fn new(velocity_ratio: f64, threshold: f64, duration: Duration) -> Self {
+        Self {
+            velocity_ratio,
+            threshold,
+            duration,
+            stalled_time: Duration::ZERO,
+        }
+    }
+
+    /// Feed a new commanded output and velocity measurement to the detector, returning `true`
+    /// if a stall has been detected.
+    ///
+    /// `dt` is the time elapsed since the last call to `update`.
+    pub fn update(&mut self, commanded: f64, measured_velocity: f64, dt: Duration) -> bool {
+        let expected_velocity = (commanded * self.velocity_ratio).abs();
+
+        if expected_velocity - measured_velocity.abs() > self.threshold {
+            self.stalled_time += dt;
+        } else {
+            self.stalled_time = Duration::ZERO;
+        }
+
+        self.is_stalled()
+    }
+
+    /// Returns `true` if the velocity gap has persisted for at least `duration`.
+    pub fn is_stalled(&self) -> bool {
+        !self.stalled_time.is_zero() && self.stalled_time >= self.duration
+    }
+
+    /// Reset the detector's internal timer.
+    pub fn reset(&mut self) {
+        self.stalled_time = Duration::ZERO;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DT: Duration = Duration::from_millis(10);
+
+    #[test]
+    fn integrator_ramps_constant_input() {
+        let mut integrator = Integrator::new();
+
+        for i in 1..=100 {
+            let sum = integrator.update(2.0, DT);
+            assert!((sum - 2.0 * 0.01 * i as f64).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn integrator_clamps_and_leaks() {
+        let mut integrator = Integrator::new().with_limit(-1.0);
+        for _ in 0..1000 {
+            integrator.update(5.0, DT);
+        }
+        assert_eq!(integrator.value(), 1.0);
+
+        let mut integrator = Integrator::new().with_leak(1.0);
+        integrator.update(1.0, Duration::from_secs(1));
+        for _ in 0..100 {
+            integrator.update(0.0, DT);
+        }
+        assert!((integrator.value() - (-1.0).exp()).abs() < 1e-9);
+
+        integrator.reset();
+        assert_eq!(integrator.value(), 0.0);
+    }
+
+    #[test]
+    fn derivative_of_ramp_is_constant() {
+        let mut derivative = Derivative::new();
+
+        assert_eq!(derivative.update(0.0, DT), 0.0);
+        for i in 1..=100 {
+            let rate = derivative.update(3.0 * 0.01 * i as f64, DT);
+            assert!((rate - 3.0).abs() < 1e-9);
+        }
+
+        // The filtered estimate converges to the same rate.
+        let mut derivative = Derivative::new().with_filter(0.5);
+        for i in 0..=100 {
+            derivative.update(3.0 * 0.01 * i as f64, DT);
+        }
+        assert!((derivative.value() - 3.0).abs() < 1e-9);
+
+        derivative.reset();
+        assert_eq!(derivative.update(100.0, DT), 0.0);
+    }
+
+    #[test]
+    fn integrator_sanitizes_leak() {
+        // A negative leak decays like a positive one instead of growing.
+        let mut integrator = Integrator::new().with_leak(-1.0);
+        integrator.update(1.0, Duration::from_secs(1));
+        integrator.update(0.0, Duration::from_secs(1));
+        assert!((integrator.value() - (-1.0).exp()).abs() < 1e-9);
+
+        // Non-finite leaks disable leakage rather than poisoning the sum.
+        for leak in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let mut integrator = Integrator::new().with_leak(leak);
+            integrator.update(1.0, Duration::from_secs(1));
+            integrator.update(0.0, Duration::ZERO);
+            assert_eq!(integrator.update(0.0, DT), 1.0);
+        }
+    }
+
+    #[test]
+    fn stall_detector_trips_after_sustained_stall() {
+        let mut detector = StallDetector::new(50.0, 100.0, Duration::from_millis(100));
+
+        // Accelerating normally at 12 volts (an expected 600 rpm).
+        for velocity in [0.0, 200.0, 400.0, 550.0, 590.0] {
+            detector.update(12.0, velocity, DT);
+        }
+        assert!(!detector.is_stalled());
+
+        // Pinned against a wall, so velocity drops to zero while still commanding full voltage.
+        for _ in 0..9 {
+            assert!(!detector.update(12.0, 5.0, DT));
+        }
+        assert!(detector.update(12.0, 5.0, DT));
+
+        // Moving freely again clears the stall.
+        assert!(!detector.update(12.0, 580.0, DT));
+    }
+
+    #[test]
+    fn stall_detector_reset_clears_stall() {
+        let mut detector = StallDetector::new(50.0, 100.0, DT);
+
+        assert!(detector.update(12.0, 0.0, DT));
+        detector.reset();
+        assert!(!detector.is_stalled());
+    }
+}
@@ -1,3 +1,11 @@
+use alloc::boxed::Box;
+use core::time::Duration;
+
+pub mod components;
+pub mod pid;
+pub mod scheduled;
+pub mod velocity;
+
 /// A closed-loop feedback controller.
 ///
 /// At its core, a feedback controller is a simple function that produces an output value
@@ -9,7 +17,7 @@ pub trait MotionController: core::fmt::Debug + Send + Sync + 'static {
     type Output;
 
     /// Produce an output value given an `error` value, which is the difference between the measured state
-    /// and the desired state (setpoint).
+    /// and the desired state (setpoint), and `dt`, the time elapsed since the last update.
     ///
     /// # Example
     ///
@@ -17,9 +25,86 @@ pub trait MotionController: core::fmt::Debug + Send + Sync + 'static {
     /// /// A basic proportional controller that multiplies the error value by a constant (2.0).
     /// /// This effectively means that the correction will increase proportional to the growth
     /// /// of the error. A high error value will produce a higher output than a lower error.
-    /// fn update(&mut self, error: f64) -> f64 {
+    /// fn update(&mut self, error: f64, _dt: Duration) -> f64 {
     /// 	error * 2.0
     /// }
     /// ```
-    fn update(&mut self, error: Self::Input) -> Self::Output;
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output;
+
+    /// Clear any internal state accumulated by previous updates (such as an integral or previous error),
+    /// as if the controller had just been constructed.
+    ///
+    /// Controllers without internal state don't need to implement this.
+    fn reset(&mut self) {}
+}
+
+/// A boxed [`MotionController`] operating on `f64` values.
+///
+/// `MotionController` is object-safe, so controllers of different types can be stored together
+/// behind a trait object. This is useful for mechanisms with several controlled axes, where each
+/// axis may use a different kind of controller:
+///
+/// ```
+/// let mut controllers: Vec<DynMotionController> = vec![
+///     Box::new(PIDController::new((1.0, 0.0, 0.1), 0.0)),
+///     Box::new(PIDController::new((0.5, 0.01, 0.0), 5.0)),
+/// ];
+/// ```
+///
+/// Calls through a `DynMotionController` use dynamic dispatch, which has a small runtime cost
+/// compared to using a concrete controller type.
+pub type DynMotionController = Box<dyn MotionController<Input = f64, Output = f64>>;
+
+impl<C: MotionController + ?Sized> MotionController for Box<C> {
+    type Input = C::Input;
+    type Output = C::Output;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        (**self).update(error, dt)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use super::*;
+    use crate::controller::pid::PIDController;
+
+    #[test]
+    fn boxed_controllers_match_unboxed() {
+        let gains = [(1.0, 0.0, 0.1), (0.5, 0.01, 0.0), (2.0, 0.5, 0.25)];
+        let dt = Duration::from_millis(10);
+
+        let mut boxed: Vec<DynMotionController> = gains
+            .iter()
+            .map(|&gains| Box::new(PIDController::new(gains, f64::INFINITY)) as DynMotionController)
+            .collect();
+        let mut unboxed: Vec<PIDController> = gains
+            .iter()
+            .map(|&gains| PIDController::new(gains, f64::INFINITY))
+            .collect();
+
+        for error in [4.0, 3.0, 1.5, -0.5] {
+            for (boxed, unboxed) in boxed.iter_mut().zip(&mut unboxed) {
+                assert_eq!(boxed.update(error, dt), unboxed.update(error, dt));
+            }
+        }
+
+        // Different gains give different outputs, so each update went to its own controller.
+        let outputs: Vec<f64> = boxed.iter_mut().map(|c| c.update(1.0, dt)).collect();
+        assert_ne!(outputs[0], outputs[1]);
+        assert_ne!(outputs[1], outputs[2]);
+
+        // Resetting through the box clears the controller's state.
+        boxed[2].reset();
+        assert_eq!(
+            boxed[2].update(1.0, dt),
+            PIDController::new(gains[2], f64::INFINITY).update(1.0, dt)
+        );
+    }
 }
@@ -1,3 +1,8 @@
+use core::time::Duration;
+use num_traits::real::Real;
+
+use super::MotionController;
+
 /// A proportional-integral-derivative (PID) feedback controller.
 ///
 /// The PID controller is a feedback control algorithm with common applications
@@ -12,8 +17,8 @@
 /// output increases with it.
 ///
 /// - The integral component of a PID controller describes the accumulation of error over time. It is the
-/// sum of all error values multiplied by a constant `ki` fed to this feedback controller when `update`
-/// is called. In practice, this component will help to correct for cases where a system slightly undershoots
+/// sum of all error values fed to this feedback controller when `update` is called (each weighted by the
+/// time elapsed since the previous update), multiplied by a constant `ki`. In practice, this component will help to correct for cases where a system slightly undershoots
 /// the setpoint ("steady-state error").
 ///
 /// 	> In some scenarios, a PID controller may be prone to *integral windup*, where a controlled system
@@ -24,7 +29,7 @@
 ///
 /// - The derivative component represents the change in error over time. The derivative component is the
 /// difference between the error given to `update` and the error given to `update` the last time it was
-/// called, multiplied by a constant `kd`. In practice, this component will apply a "damping" effect to the
+/// called (divided by the time elapsed between the two calls), multiplied by a constant `kd`. In practice, this component will apply a "damping" effect to the
 /// controller, preventing sudden jerks or changes to the output.
 ///
 /// # Tuning
@@ -33,6 +38,8 @@
 /// controlled system to reach a setpoint in a reasonable amount of time without oscillations (rapid,
 /// unpredictable changes in output). Tuning methods are typically different depending on the application
 /// that the PID controller is used in. Typically a tuned `kp` will be much higher than `ki` and `kd`.
+///
+/// A [`Default`] controller has all gains set to `0.0`, and will always output `0.0` until it is tuned.
 #[derive(Clone, PartialEq, Debug, Copy, Default)]
 pub struct PIDController {
     /// The proportional gain constant.
@@ -52,6 +59,11 @@ pub struct PIDController {
 
 impl PIDController {
     /// Construct a new [`PIDController`] from gain constants.
+    ///
+    /// `gains` is a tuple of (`kp`, `ki`, `kd`). `integral_threshold` is the maximum absolute error at which
+    /// the integral component will accumulate. Error values outside of this range won't be integrated.
+    ///
+    /// For a more self-documenting way to construct a controller, see [`PIDController::builder`].
     pub fn new(gains: (f64, f64, f64), integral_threshold: f64) -> Self {
         Self {
             kp: gains.0,
@@ -62,11 +74,45 @@ impl PIDController {
         }
     }
 
+    /// Create a [`PIDControllerBuilder`] for constructing a [`PIDController`] with named parameters.
+    ///
+    /// Gains that aren't set default to `0.0`. Unlike [`PIDController::new`], the integral threshold is
+    /// unbounded unless it is set, so setting `ki` alone is enough to enable the integral component.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let pid = PIDController::builder()
+    ///     .kp(1.0)
+    ///     .ki(0.01)
+    ///     .kd(0.1)
+    ///     .integral_threshold(5.0)
+    ///     .build();
+    /// ```
+    pub fn builder() -> PIDControllerBuilder {
+        PIDControllerBuilder::default()
+    }
+
+    /// Construct a new [`PIDController`] using the classic Ziegler-Nichols tuning rules.
+    ///
+    /// `ku` is the *ultimate gain*, which is the value of `kp` (with `ki` and `kd` at `0.0`) that causes
+    /// the system to oscillate with a consistent amplitude. `tu` is the period of those oscillations. The
+    /// resulting gains are `kp = 0.6 * ku`, `ki = 1.2 * ku / tu`, and `kd = 0.075 * ku * tu`.
+    ///
+    /// Ziegler-Nichols tuning tends to be fairly aggressive, so these gains should be treated as a starting
+    /// point for manual tuning rather than a final tune. The integral threshold is left unbounded, and can be
+    /// narrowed using [`PIDController::set_integral_threshold`].
+    pub fn from_ziegler_nichols(ku: f64, tu: Duration) -> Self {
+        let tu = tu.as_secs_f64();
+
+        Self::new((0.6 * ku, 1.2 * ku / tu, 0.075 * ku * tu), f64::INFINITY)
+    }
+
     /// Get the current PID gains as a tuple (`kp`, `ki`, `kd`).
     pub fn gains(&self) -> (f64, f64, f64) {
         (self.kp, self.ki, self.kd)
     }
-    
+
     pub fn integral_threshold(&self) -> f64 {
         self.integral_threshold
     }
@@ -83,22 +129,222 @@ impl PIDController {
     }
 }
 
+/// A builder for constructing a [`PIDController`], created through [`PIDController::builder`].
+#[derive(Clone, PartialEq, Debug, Copy)]
+pub struct PIDControllerBuilder {
+    gains: (f64, f64, f64),
+    integral_threshold: f64,
+}
+
+impl Default for PIDControllerBuilder {
+    fn default() -> Self {
+        Self {
+            gains: (0.0, 0.0, 0.0),
+            integral_threshold: f64::INFINITY,
+        }
+    }
+}
+
+impl PIDControllerBuilder {
+    /// Set the proportional gain constant.
+    pub fn kp(mut self, kp: f64) -> Self {
+        self.gains.0 = kp;
+        self
+    }
+
+    /// Set the integral gain constant.
+    pub fn ki(mut self, ki: f64) -> Self {
+        self.gains.1 = ki;
+        self
+    }
+
+    /// Set the derivative gain constant.
+    pub fn kd(mut self, kd: f64) -> Self {
+        self.gains.2 = kd;
+        self
+    }
+
+    /// Set the maximum absolute error at which the integral component will accumulate.
+    pub fn integral_threshold(mut self, integral_threshold: f64) -> Self {
+        self.integral_threshold = integral_threshold;
+        self
+    }
+
+    /// Construct the [`PIDController`].
+    pub fn build(self) -> PIDController {
+        PIDController::new(self.gains, self.integral_threshold)
+    }
+}
+
 impl MotionController for PIDController {
     type Input = f64;
     type Output = f64;
 
+    /// Produce an output value from an `error` value and the time elapsed since the last update.
+    ///
+    /// If `dt` is zero (which can happen on the very first update), the integral and derivative
+    /// components can't be computed, so only the proportional component is returned and the
+    /// controller's internal state is left untouched.
     fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        if dt.is_zero() {
+            return error * self.kp;
+        }
+
+        let dt = dt.as_secs_f64();
+
         if error.abs() < self.integral_threshold {
-            self.integral += error;
+            self.integral += error * dt;
         }
 
         if error.signum() != self.previous_error.signum() {
             self.integral = 0.0;
         }
-        
-        let derivative = error - self.previous_error;
+
+        let derivative = (error - self.previous_error) / dt;
         self.previous_error = error;
 
-        (error * self.kp) + (self.integral * self.ki * dt.as_secs_f64()) + (derivative * self.kd / dt.as_secs_f64())
+        (error * self.kp) + (self.integral * self.ki) + (derivative * self.kd)
+    }
+
+    fn reset(&mut self) {
+        self.integral = 0.0;
+        self.previous_error = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_matches_positional_constructor() {
+        let built = PIDController::builder()
+            .kp(1.0)
+            .ki(0.1)
+            .kd(0.01)
+            .integral_threshold(5.0)
+            .build();
+
+        assert_eq!(built, PIDController::new((1.0, 0.1, 0.01), 5.0));
+    }
+
+    #[test]
+    fn builder_integrates_without_threshold() {
+        let mut pid = PIDController::builder().ki(1.0).build();
+
+        assert_eq!(pid.update(100.0, Duration::from_secs(1)), 100.0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn zero_dt_returns_finite_output() {
+        let mut pid = PIDController::new((2.0, 1.0, 1.0), f64::INFINITY);
+
+        assert_eq!(pid.update(3.0, Duration::ZERO), 6.0);
+        assert!(pid.update(3.0, Duration::from_millis(10)).is_finite());
+        assert!(pid.update(-3.0, Duration::ZERO).is_finite());
+    }
+
+    const DT: Duration = Duration::from_millis(100);
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() < 1e-9,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn proportional_term() {
+        let mut pid = PIDController::new((2.0, 0.0, 0.0), 0.0);
+
+        for error in [4.0, -1.5, 0.0, 10.0] {
+            assert_close(pid.update(error, DT), 2.0 * error);
+        }
+    }
+
+    #[test]
+    fn integral_term() {
+        let mut pid = PIDController::new((0.0, 1.0, 0.0), f64::INFINITY);
+
+        // A constant error of 2.0 integrates to 2.0 * t.
+        for i in 1..=5 {
+            assert_close(pid.update(2.0, DT), 2.0 * 0.1 * i as f64);
+        }
+    }
+
+    #[test]
+    fn derivative_term() {
+        let mut pid = PIDController::new((0.0, 0.0, 1.0), 0.0);
+
+        // The first update measures the change from an initial error of zero.
+        assert_close(pid.update(1.0, DT), 10.0);
+
+        // An error ramping by 0.5 every 100ms has a derivative of 5.0.
+        for i in 1..=5 {
+            assert_close(pid.update(1.0 + 0.5 * i as f64, DT), 5.0);
+        }
+    }
+
+    #[test]
+    fn combined_terms() {
+        let mut pid = PIDController::new((2.0, 0.5, 0.1), f64::INFINITY);
+
+        // integral = 0.4, derivative = 40.0
+        assert_close(pid.update(4.0, DT), 8.0 + 0.2 + 4.0);
+        // integral = 0.6, derivative = -20.0
+        assert_close(pid.update(2.0, DT), 4.0 + 0.3 - 2.0);
+        // integral = 0.7, derivative = -10.0
+        assert_close(pid.update(1.0, DT), 2.0 + 0.35 - 1.0);
+    }
+
+    #[test]
+    fn integral_threshold_gates_accumulation() {
+        let mut pid = PIDController::new((0.0, 1.0, 0.0), 1.0);
+
+        assert_close(pid.update(5.0, DT), 0.0);
+        assert_close(pid.update(0.5, DT), 0.05);
+        assert_close(pid.update(5.0, DT), 0.05);
+        assert_close(pid.update(0.5, DT), 0.1);
+    }
+
+    #[test]
+    fn integral_resets_when_error_changes_sign() {
+        let mut pid = PIDController::new((0.0, 1.0, 0.0), f64::INFINITY);
+
+        pid.update(1.0, DT);
+        assert_close(pid.update(1.0, DT), 0.2);
+        assert_close(pid.update(-1.0, DT), 0.0);
+        assert_close(pid.update(-1.0, DT), -0.1);
+    }
+
+    #[test]
+    fn zero_dt_returns_proportional_term_only() {
+        let mut pid = PIDController::new((2.0, 0.5, 0.1), f64::INFINITY);
+        let mut reference = pid;
+
+        assert_eq!(pid.update(3.0, Duration::ZERO), 6.0);
+
+        // The zero-dt update didn't change any internal state.
+        assert_eq!(pid.update(4.0, DT), reference.update(4.0, DT));
+    }
+
+    #[test]
+    fn ziegler_nichols_gains() {
+        let controller = PIDController::from_ziegler_nichols(2.0, Duration::from_millis(500));
+        let (kp, ki, kd) = controller.gains();
+
+        assert!((kp - 1.2).abs() < 1e-12);
+        assert!((ki - 4.8).abs() < 1e-12);
+        assert!((kd - 0.075).abs() < 1e-12);
+        assert_eq!(controller.integral_threshold(), f64::INFINITY);
+    }
+
+    #[test]
+    fn default_controller_outputs_zero() {
+        let mut controller = PIDController::default();
+
+        for error in [-100.0, -1.0, 0.0, 1.0, 100.0] {
+            assert_eq!(controller.update(error, Duration::from_millis(10)), 0.0);
+        }
+    }
+}
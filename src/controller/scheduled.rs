@@ -0,0 +1,143 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use super::{pid::PIDController, MotionController};
+
+/// A [`PIDController`] whose gains are scheduled based on the robot's velocity.
+///
+/// Some systems behave differently at different speeds (for example, a drivetrain may shift its weight
+/// while moving quickly). A `VelocityScheduledPID` stores a table of `(velocity, gains)` breakpoints, and
+/// picks gains for each update based on the most recent velocity reading given to
+/// [`set_velocity`](VelocityScheduledPID::set_velocity).
+///
+/// Between two breakpoints, gains are linearly interpolated. Below the lowest or above the highest
+/// breakpoint, that breakpoint's gains are used as-is. Velocity readings can come from any source (such
+/// as motor velocity or a tracking system), as long as they're in the same units as the table.
+///
+/// A [`Default`] controller has an empty schedule, and will always output `0.0`.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct VelocityScheduledPID {
+    pid: PIDController,
+    schedule: Vec<(f64, (f64, f64, f64))>,
+    velocity: f64,
+}
+
+impl VelocityScheduledPID {
+    /// Construct a new [`VelocityScheduledPID`] from a table of `(velocity, (kp, ki, kd))` breakpoints.
+    ///
+    /// Breakpoints don't need to be given in any particular order.
+    pub fn new(schedule: impl Into<Vec<(f64, (f64, f64, f64))>>, integral_threshold: f64) -> Self {
+        let mut schedule = schedule.into();
+        schedule.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        Self {
+            pid: PIDController::new((0.0, 0.0, 0.0), integral_threshold),
+            schedule,
+            velocity: 0.0,
+        }
+    }
+
+    /// Provide the latest velocity reading used for selecting gains.
+    pub fn set_velocity(&mut self, velocity: f64) {
+        self.velocity = velocity;
+    }
+
+    /// Compute the scheduled gains (`kp`, `ki`, `kd`) at a given velocity.
+    pub fn gains_at(&self, velocity: f64) -> (f64, f64, f64) {
+        let (Some(first), Some(last)) = (self.schedule.first(), self.schedule.last()) else {
+            return (0.0, 0.0, 0.0);
+        };
+
+        if velocity <= first.0 {
+            return first.1;
+        }
+        if velocity >= last.0 {
+            return last.1;
+        }
+
+        for window in self.schedule.windows(2) {
+            let ((v0, g0), (v1, g1)) = (window[0], window[1]);
+
+            if velocity <= v1 {
+                let t = if v1 == v0 {
+                    1.0
+                } else {
+                    (velocity - v0) / (v1 - v0)
+                };
+
+                return (
+                    g0.0 + (g1.0 - g0.0) * t,
+                    g0.1 + (g1.1 - g0.1) * t,
+                    g0.2 + (g1.2 - g0.2) * t,
+                );
+            }
+        }
+
+        last.1
+    }
+}
+
+impl MotionController for VelocityScheduledPID {
+    type Input = f64;
+    type Output = f64;
+
+    fn update(&mut self, error: Self::Input, dt: Duration) -> Self::Output {
+        self.pid.set_gains(self.gains_at(self.velocity));
+        self.pid.update(error, dt)
+    }
+
+    fn reset(&mut self) {
+        self.pid.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule() -> VelocityScheduledPID {
+        VelocityScheduledPID::new(
+            [(100.0, (2.0, 0.2, 0.02)), (0.0, (1.0, 0.0, 0.0))],
+            f64::INFINITY,
+        )
+    }
+
+    #[test]
+    fn gains_at_breakpoints() {
+        let controller = schedule();
+
+        assert_eq!(controller.gains_at(0.0), (1.0, 0.0, 0.0));
+        assert_eq!(controller.gains_at(100.0), (2.0, 0.2, 0.02));
+    }
+
+    #[test]
+    fn gains_interpolate_between_breakpoints() {
+        let (kp, ki, kd) = schedule().gains_at(25.0);
+
+        assert!((kp - 1.25).abs() < 1e-12);
+        assert!((ki - 0.05).abs() < 1e-12);
+        assert!((kd - 0.005).abs() < 1e-12);
+    }
+
+    #[test]
+    fn gains_clamp_outside_breakpoints() {
+        let controller = schedule();
+
+        assert_eq!(controller.gains_at(-50.0), (1.0, 0.0, 0.0));
+        assert_eq!(controller.gains_at(500.0), (2.0, 0.2, 0.02));
+        assert_eq!(
+            VelocityScheduledPID::default().gains_at(50.0),
+            (0.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn update_uses_scheduled_gains() {
+        let mut controller = schedule();
+        controller.set_velocity(100.0);
+
+        // A unit error held for one second gives unit P, I, and D terms, so the output is kp + ki + kd.
+        let output = controller.update(1.0, Duration::from_secs(1));
+        assert!((output - 2.22).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,238 @@
+use alloc::sync::Arc;
+use core::{fmt, time::Duration};
+use num_traits::real::Real;
+use vexide::{
+    async_runtime::{
+        task::{spawn, Task},
+        time::sleep,
+    },
+    core::sync::Mutex,
+    devices::smart::Motor,
+};
+
+use crate::{
+    controller::MotionController,
+    devices::{DriveMotors, FaultCounter},
+};
+
+/// Holds a group of motors at a target velocity.
+///
+/// This is intended for mechanisms such as flywheels and intakes that need to spin at a consistent
+/// speed regardless of load. Each update, the controller applies a feedforward voltage proportional
+/// to the target velocity (`kv * target`), then corrects for any remaining error using a feedback
+/// controller such as a [`PIDController`](crate::controller::pid::PIDController).
+///
+/// Velocity is measured in RPM using the average of the motors' internal velocity readings, so
+/// gearing between the motors and the mechanism should be accounted for in the target.
+#[derive(Debug)]
+pub struct VelocityController {
+    motors: DriveMotors,
+    target: Arc<Mutex<f64>>,
+    velocity: Arc<Mutex<f64>>,
+    faults: Arc<FaultCounter>,
+    _task: Task<()>,
+}
+
+/// An error returned by [`VelocityController::spin_up_to`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpinUpError {
+    /// The motors persistently failed to accept a voltage (see [`VelocityController::is_faulted`]).
+    Faulted,
+
+    /// The measured velocity didn't reach the target before the timeout.
+    TimedOut,
+}
+
+impl fmt::Display for SpinUpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Faulted => write!(f, "motors failed to accept a voltage while spinning up"),
+            Self::TimedOut => write!(f, "target velocity wasn't reached before the timeout"),
+        }
+    }
+}
+
+impl core::error::Error for SpinUpError {}
+
+/// Combine a feedforward and feedback output, limiting their sum to `max`.
+///
+/// The feedforward output represents the voltage needed to hold the target velocity, so it's preserved
+/// under saturation, while the feedback output is limited to whatever headroom remains. If the feedforward
+/// output alone exceeds `max`, it's clamped to `max` and the feedback output is discarded entirely.
+fn saturate(feedforward: f64, feedback: f64, max: f64) -> f64 {
+    let feedforward = feedforward.clamp(-max, max);
+
+    feedforward + feedback.clamp(-max - feedforward, max - feedforward)
+}
+
+/// Compute the voltage to apply for a single update.
+fn control_output(
+    controller: &mut impl MotionController<Input = f64, Output = f64>,
+    kv: f64,
+    target: f64,
+    measured: f64,
+) -> f64 {
+    // Don't fight the mechanism's momentum when we're asked to stop. The controller is reset so that
+    // integral built up during this spin-up doesn't carry into the next one.
+    if target == 0.0 {
+        controller.reset();
+        return 0.0;
+    }
+
+    let feedback = controller.update(target - measured, Motor::DATA_READ_INTERVAL);
+
+    saturate(kv * target, feedback, Motor::MAX_VOLTAGE)
+}
+
+impl VelocityController {
+    /// Construct a new [`VelocityController`] from a group of motors, a feedback controller, and a
+    /// feedforward velocity constant `kv` (volts per RPM).
+    pub fn new(
+        motors: DriveMotors,
+        mut controller: impl MotionController<Input = f64, Output = f64>,
+        kv: f64,
+    ) -> Self {
+        let target = Arc::new(Mutex::new(0.0));
+        let velocity = Arc::new(Mutex::new(0.0));
+        let faults = Arc::new(FaultCounter::new());
+
+        Self {
+            motors: Arc::clone(&motors),
+            target: Arc::clone(&target),
+            velocity: Arc::clone(&velocity),
+            faults: Arc::clone(&faults),
+            _task: spawn(async move {
+                loop {
+                    let target = *target.lock().await;
+                    let mut motors = motors.lock().await;
+
+                    let mut velocity_sum = 0.0;
+                    for motor in motors.iter() {
+                        velocity_sum += f64::from(motor.velocity().unwrap_or_default());
+                    }
+                    let measured = velocity_sum / (motors.len().max(1) as f64);
+                    *velocity.lock().await = measured;
+
+                    let output = control_output(&mut controller, kv, target, measured);
+
+                    faults.record(motors.iter_mut().map(|motor| motor.set_voltage(output)));
+
+                    drop(motors);
+                    sleep(Motor::DATA_READ_INTERVAL).await;
+                }
+            }),
+        }
+    }
+
+    /// Set the target velocity in RPM.
+    pub async fn set_target(&mut self, rpm: f64) {
+        *self.target.lock().await = rpm;
+    }
+
+    /// Get the target velocity in RPM.
+    pub async fn target(&self) -> f64 {
+        *self.target.lock().await
+    }
+
+    /// Get the most recently measured velocity in RPM.
+    pub async fn velocity(&self) -> f64 {
+        *self.velocity.lock().await
+    }
+
+    /// Set the target velocity, then wait until the measured velocity is within `tolerance` RPM
+    /// of the target.
+    ///
+    /// Returns an error if the motors become faulted, or if the target isn't reached within `timeout`
+    /// (for example, if it's faster than the mechanism can spin). In either case the target is left
+    /// set, so the controller keeps trying to reach it.
+    pub async fn spin_up_to(
+        &mut self,
+        rpm: f64,
+        tolerance: f64,
+        timeout: Duration,
+    ) -> Result<(), SpinUpError> {
+        self.set_target(rpm).await;
+
+        let mut elapsed = Duration::ZERO;
+        while (self.velocity().await - rpm).abs() > tolerance {
+            if self.is_faulted() {
+                return Err(SpinUpError::Faulted);
+            }
+            if elapsed >= timeout {
+                return Err(SpinUpError::TimedOut);
+            }
+
+            sleep(Motor::DATA_READ_INTERVAL).await;
+            elapsed += Motor::DATA_READ_INTERVAL;
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the controller has persistently failed to command its motors.
+    ///
+    /// See [`FaultCounter`] for when a fault is reported and cleared.
+    pub fn is_faulted(&self) -> bool {
+        self.faults.is_faulted()
+    }
+
+    /// Get the motors driven by this controller.
+    pub fn motors(&self) -> DriveMotors {
+        Arc::clone(&self.motors)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::pid::PIDController;
+
+    #[test]
+    fn reaches_target_on_first_order_plant() {
+        // A flywheel with a free speed of 600 rpm at 12 volts, a 100ms time constant, and a constant load
+        // slowing it by 500 rpm/s that the feedforward term alone can't account for.
+        const GAIN: f64 = 600.0 / Motor::MAX_VOLTAGE;
+        const TIME_CONSTANT: f64 = 0.1;
+        const LOAD: f64 = 500.0;
+
+        let mut controller = PIDController::new((0.02, 0.1, 0.0), f64::INFINITY);
+        let dt = Motor::DATA_READ_INTERVAL.as_secs_f64();
+        let mut velocity = 0.0;
+
+        for _ in 0..300 {
+            let voltage = control_output(&mut controller, 1.0 / GAIN, 400.0, velocity);
+            velocity += ((GAIN * voltage - velocity) / TIME_CONSTANT - LOAD) * dt;
+        }
+
+        assert!((velocity - 400.0).abs() < 5.0, "settled at {velocity} rpm");
+    }
+
+    #[test]
+    fn stopping_resets_controller() {
+        let mut controller = PIDController::new((0.0, 1.0, 0.0), f64::INFINITY);
+
+        for _ in 0..10 {
+            control_output(&mut controller, 0.0, 400.0, 0.0);
+        }
+        assert_eq!(control_output(&mut controller, 0.0, 0.0, 0.0), 0.0);
+
+        // With the integral cleared, the next update only integrates a single step of error.
+        let output = control_output(&mut controller, 0.0, 100.0, 0.0);
+        assert!((output - 100.0 * Motor::DATA_READ_INTERVAL.as_secs_f64()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn saturation_clips_feedback_before_feedforward() {
+        // Feedback is limited to the headroom left by feedforward.
+        assert_eq!(saturate(10.0, 5.0, 12.0), 12.0);
+        assert_eq!(saturate(10.0, -5.0, 12.0), 5.0);
+        assert_eq!(saturate(-10.0, -5.0, 12.0), -12.0);
+
+        // Feedforward alone past the limit is clamped, and feedback can only pull it back.
+        assert_eq!(saturate(15.0, 3.0, 12.0), 12.0);
+        assert_eq!(saturate(15.0, -3.0, 12.0), 9.0);
+
+        // Unsaturated outputs are summed as usual.
+        assert_eq!(saturate(4.0, 2.0, 12.0), 6.0);
+    }
+}
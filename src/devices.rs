@@ -1,8 +1,9 @@
 use alloc::{sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicU32, Ordering};
 use vexide::{
-    core::sync::{Mutex, MutexGuard},
+    core::sync::Mutex,
     devices::{
-        adi::{encoder::EncoderError, AdiEncoder},
+        adi::{analog::AdiAnalogIn, encoder::EncoderError, AdiEncoder},
         position::Position,
         smart::{
             motor::{Motor, MotorError},
@@ -36,6 +37,9 @@ macro_rules! impl_rotary_sensor {
 
 impl_rotary_sensor!(Motor, position, MotorError);
 impl_rotary_sensor!(RotationSensor, position, PortError);
+// Three-wire optical shaft encoders must be wired with their two cables in adjacent ADI ports
+// (top cable in the odd port). vexide handles the tick-to-position conversion internally, assuming
+// the 360 ticks per revolution resolution of the VEX optical shaft encoder.
 impl_rotary_sensor!(AdiEncoder, position, EncoderError);
 
 impl RotarySensor for Vec<Motor> {
@@ -52,24 +56,139 @@ impl RotarySensor for Vec<Motor> {
     }
 }
 
+/// A potentiometer calibrated to report the angle of a bounded mechanism.
+///
+/// Potentiometers report an analog reading proportional to their rotation, but only across a limited
+/// range of motion. This adapter linearly maps raw readings between two calibrated endpoints to a
+/// corresponding range of angles (in degrees), clamping readings that fall outside of the calibrated
+/// range.
+///
+/// Because a potentiometer's output is not continuous (it cannot rotate freely past its endpoints),
+/// this should only be used for mechanisms with a limited range of motion such as arms and lifts,
+/// and never as a heading source or tracking wheel.
+#[derive(Debug, PartialEq)]
+pub struct PotentiometerSensor {
+    /// The analog input the potentiometer is wired to.
+    pub analog_in: AdiAnalogIn,
+
+    /// Raw readings at each end of the mechanism's range of motion.
+    pub value_range: (u16, u16),
+
+    /// Angles (in degrees) corresponding to each reading in `value_range`.
+    pub angle_range: (f64, f64),
+}
+
+impl PotentiometerSensor {
+    /// Construct a new [`PotentiometerSensor`] from an analog input and calibrated endpoints.
+    pub fn new(analog_in: AdiAnalogIn, value_range: (u16, u16), angle_range: (f64, f64)) -> Self {
+        Self {
+            analog_in,
+            value_range,
+            angle_range,
+        }
+    }
+
+    /// Map a raw analog reading to an angle in degrees using the calibrated endpoints.
+    pub fn angle_from_value(&self, value: u16) -> f64 {
+        map_reading(value, self.value_range, self.angle_range)
+    }
+}
+
+/// Linearly map a raw reading within `value_range` to an angle within `angle_range`, clamping readings
+/// outside of the calibrated range to its endpoints.
+fn map_reading(value: u16, value_range: (u16, u16), angle_range: (f64, f64)) -> f64 {
+    let (min_value, max_value) = (value_range.0 as f64, value_range.1 as f64);
+    let (min_angle, max_angle) = angle_range;
+
+    if min_value == max_value {
+        return min_angle;
+    }
+
+    let t = ((value as f64 - min_value) / (max_value - min_value)).clamp(0.0, 1.0);
+
+    min_angle + (max_angle - min_angle) * t
+}
+
+impl RotarySensor for PotentiometerSensor {
+    type Error = PortError;
+
+    fn position(&self) -> Result<Position, Self::Error> {
+        Ok(Position::from_degrees(
+            self.angle_from_value(self.analog_in.value()?),
+        ))
+    }
+}
+
+/// An error returned when reading a sensor shared behind an `Arc<Mutex<T>>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SharedSensorError<E> {
+    /// The sensor is currently locked by another task.
+    Busy,
+
+    /// The underlying sensor returned an error.
+    Sensor(E),
+}
+
 /// Blanket implementation for all Arc<Mutex<T>> wrappers of already implemented sensors.
+///
+/// Shared sensors (such as drive motors used for both driving and tracking) may be locked by another task
+/// when they're read. Since vexide's runtime is cooperative, spinning until the lock is released would never
+/// give the other task a chance to release it, so this returns [`SharedSensorError::Busy`] instead and leaves
+/// it up to the caller to skip the reading.
 impl<T: RotarySensor> RotarySensor for Arc<Mutex<T>> {
-    type Error = <T as RotarySensor>::Error;
+    type Error = SharedSensorError<<T as RotarySensor>::Error>;
 
     fn position(&self) -> Result<Position, Self::Error> {
-        let mut guard: Option<MutexGuard<'_, T>> = None;
+        match self.try_lock() {
+            Some(guard) => guard.position().map_err(SharedSensorError::Sensor),
+            None => Err(SharedSensorError::Busy),
+        }
+    }
+}
 
-        while match self.try_lock() {
-            Some(lock) => {
-                guard = Some(lock);
-                false
-            }
-            None => true,
-        } {
-            core::hint::spin_loop();
+/// Counts consecutive updates in which a group of motors failed to accept a voltage.
+///
+/// A single failed write (for example, from a momentary cable disconnect) isn't treated as a fault. A
+/// fault is only reported once at least one motor has failed for [`FaultCounter::THRESHOLD`] consecutive
+/// updates, and clears as soon as every motor accepts a write again.
+///
+/// The count is atomic, so a counter can be shared through an [`Arc`] between the task writing to the
+/// motors and the code checking for faults.
+#[derive(Debug, Default)]
+pub struct FaultCounter {
+    failed_updates: AtomicU32,
+}
+
+impl FaultCounter {
+    /// The number of consecutive failed updates before a fault is reported.
+    pub const THRESHOLD: u32 = 10;
+
+    /// Construct a new [`FaultCounter`] with no failed updates.
+    pub const fn new() -> Self {
+        Self {
+            failed_updates: AtomicU32::new(0),
+        }
+    }
+
+    /// Record the results of writing to every motor in a group during a single update.
+    ///
+    /// The update counts as failed if any write failed. Every result is consumed, so writes performed
+    /// lazily by an iterator still reach the remaining motors after one of them fails.
+    pub fn record<E>(&self, results: impl IntoIterator<Item = Result<(), E>>) {
+        let failed = results
+            .into_iter()
+            .fold(false, |failed, result| failed | result.is_err());
+
+        if failed {
+            self.failed_updates.fetch_add(1, Ordering::Relaxed);
+        } else {
+            self.failed_updates.store(0, Ordering::Relaxed);
         }
+    }
 
-        guard.unwrap().position()
+    /// Returns `true` if writes have failed for at least [`FaultCounter::THRESHOLD`] consecutive updates.
+    pub fn is_faulted(&self) -> bool {
+        self.failed_updates.load(Ordering::Relaxed) >= Self::THRESHOLD
     }
 }
 
@@ -91,3 +210,72 @@ macro_rules! drive_motors {
     };
 }
 pub use drive_motors;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn potentiometer_maps_endpoints_to_angle_range() {
+        let (value_range, angle_range) = ((400, 3600), (-45.0, 90.0));
+
+        assert_eq!(map_reading(400, value_range, angle_range), -45.0);
+        assert_eq!(map_reading(3600, value_range, angle_range), 90.0);
+        assert_eq!(map_reading(2000, value_range, angle_range), 22.5);
+
+        // Readings past the calibrated endpoints are clamped.
+        assert_eq!(map_reading(0, value_range, angle_range), -45.0);
+        assert_eq!(map_reading(4095, value_range, angle_range), 90.0);
+
+        // A potentiometer mounted in reverse has its value range flipped.
+        assert_eq!(map_reading(3600, (3600, 400), angle_range), -45.0);
+    }
+
+    #[test]
+    fn fault_counter_reports_persistent_failures() {
+        let faults = FaultCounter::new();
+
+        for _ in 0..FaultCounter::THRESHOLD - 1 {
+            faults.record([Err::<(), ()>(())]);
+        }
+        assert!(!faults.is_faulted());
+
+        faults.record([Err::<(), ()>(())]);
+        assert!(faults.is_faulted());
+    }
+
+    #[test]
+    fn fault_counter_clears_on_success() {
+        let faults = FaultCounter::new();
+
+        for _ in 0..FaultCounter::THRESHOLD {
+            faults.record([Err::<(), ()>(())]);
+        }
+        faults.record([Ok::<(), ()>(()), Ok(())]);
+        assert!(!faults.is_faulted());
+
+        // The count starts over, so a fault needs another full run of failures.
+        for _ in 0..FaultCounter::THRESHOLD - 1 {
+            faults.record([Err::<(), ()>(())]);
+        }
+        assert!(!faults.is_faulted());
+    }
+
+    #[test]
+    fn fault_counter_counts_single_motor_failures() {
+        let faults = FaultCounter::new();
+        let mut writes = 0;
+
+        for _ in 0..FaultCounter::THRESHOLD {
+            faults.record(
+                [Ok(()), Err(()), Ok(())]
+                    .into_iter()
+                    .inspect(|_| writes += 1),
+            );
+        }
+
+        assert!(faults.is_faulted());
+        // A failed write doesn't stop the remaining motors from being written to.
+        assert_eq!(writes, 3 * FaultCounter::THRESHOLD);
+    }
+}
@@ -1,16 +1,21 @@
 use core::{
+    f64::consts::TAU,
+    fmt,
     future::Future,
     pin::{pin, Pin},
-    sync::atomic::{AtomicBool, Ordering},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
     task::{Context, Poll},
+    time::Duration,
 };
 
 use crate::{
-    commands::Command,
-    devices::DriveMotors,
-    tracking::{Tracking, TrackingContext},
+    commands::{hold::HoldPose, timed::TimedVoltages, Command},
+    controller::MotionController,
+    devices::{DriveMotors, FaultCounter, RotarySensor},
+    math::Vec2,
+    tracking::{self, ParallelWheelTracking, Tracking, TrackingContext},
 };
-use alloc::{boxed::Box, sync::Arc};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use num_traits::real::Real;
 use replace_with::replace_with;
 use vexide::{
@@ -18,14 +23,25 @@ use vexide::{
         task::{spawn, Task},
         time::sleep,
     },
-    core::sync::{Mutex, MutexGuard, MutexLockFuture},
+    core::sync::{Mutex, MutexLockFuture},
     devices::smart::Motor,
 };
 
+/// A side of a [`DifferentialDrivetrain`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
 #[derive(Debug)]
 pub struct DifferentialDrivetrain<T: Tracking> {
     left_motors: DriveMotors,
     right_motors: DriveMotors,
+    left_inverted: Arc<AtomicBool>,
+    right_inverted: Arc<AtomicBool>,
+    max_voltage: Arc<AtomicU64>,
+    faults: Arc<FaultCounter>,
     tracking: Arc<Mutex<T>>,
     command: Arc<Mutex<Box<dyn Command<Output = Voltages>>>>,
     _task: Task<()>,
@@ -33,27 +49,50 @@ pub struct DifferentialDrivetrain<T: Tracking> {
 
 impl<T: Tracking> DifferentialDrivetrain<T> {
     pub fn new(left_motors: DriveMotors, right_motors: DriveMotors, tracking: T) -> Self {
-        let started = Arc::new(AtomicBool::new(false));
         let command = Arc::new(Mutex::new(
             Box::new(Voltages::default()) as Box<dyn Command<Output = Voltages>>
         ));
         let tracking = Arc::new(Mutex::new(tracking));
+        let left_inverted = Arc::new(AtomicBool::new(false));
+        let right_inverted = Arc::new(AtomicBool::new(false));
+        let max_voltage = Arc::new(AtomicU64::new(Motor::MAX_VOLTAGE.to_bits()));
+        let faults = Arc::new(FaultCounter::new());
 
         Self {
             left_motors: Arc::clone(&left_motors),
             right_motors: Arc::clone(&right_motors),
+            left_inverted: Arc::clone(&left_inverted),
+            right_inverted: Arc::clone(&right_inverted),
+            max_voltage: Arc::clone(&max_voltage),
+            faults: Arc::clone(&faults),
             tracking: Arc::clone(&tracking),
             command: Arc::clone(&command),
             _task: spawn(async move {
-                while started.load(Ordering::Relaxed) {
-                    let Voltages(left, right) =
-                        command.lock().await.update(tracking.lock().await.update());
+                loop {
+                    // Tracking is updated before locking the command, so the command's lock is never held
+                    // across an await point. This lets `Execute` always inspect the command without waiting.
+                    let ctx = tracking.lock().await.update();
+                    let Voltages(left, right) = apply_limits(
+                        command.lock().await.update(ctx),
+                        f64::from_bits(max_voltage.load(Ordering::Relaxed)),
+                        left_inverted.load(Ordering::Relaxed),
+                        right_inverted.load(Ordering::Relaxed),
+                    );
 
-                    for motor in left_motors.lock().await.iter_mut() {
-                        motor.set_voltage(left).unwrap();
-                    }
-                    for motor in right_motors.lock().await.iter_mut() {
-                        motor.set_voltage(right).unwrap();
+                    {
+                        let mut left_motors = left_motors.lock().await;
+                        let mut right_motors = right_motors.lock().await;
+
+                        faults.record(
+                            left_motors
+                                .iter_mut()
+                                .map(|motor| motor.set_voltage(left))
+                                .chain(
+                                    right_motors
+                                        .iter_mut()
+                                        .map(|motor| motor.set_voltage(right)),
+                                ),
+                        );
                     }
 
                     sleep(Motor::DATA_READ_INTERVAL).await;
@@ -63,12 +102,166 @@ impl<T: Tracking> DifferentialDrivetrain<T> {
     }
 
     pub fn execute(&mut self, cmd: impl Command<Output = Voltages>) -> Execute<Voltages> {
-        Execute::SetCommand {
-            guard: self.command.lock(),
-            command: Box::new(cmd) as Box<dyn Command<Output = Voltages>>,
+        Execute::new(&self.command, Box::new(cmd))
+    }
+
+    /// Drive the robot using arcade controls.
+    ///
+    /// `throttle` and `turn` are expected to be in the range [-1, 1] (such as joystick readings),
+    /// where positive `turn` values turn the robot clockwise.
+    pub fn arcade_drive(&mut self, throttle: f64, turn: f64) -> Execute<'_, Voltages> {
+        self.execute(
+            Voltages::from_arcade(throttle * Motor::MAX_VOLTAGE, turn * Motor::MAX_VOLTAGE)
+                .normalized(Motor::MAX_VOLTAGE),
+        )
+    }
+
+    /// Drive the robot using tank controls.
+    ///
+    /// `left` and `right` are expected to be in the range [-1, 1] (such as joystick readings).
+    pub fn tank_drive(&mut self, left: f64, right: f64) -> Execute<'_, Voltages> {
+        self.execute(
+            Voltages(left * Motor::MAX_VOLTAGE, right * Motor::MAX_VOLTAGE)
+                .normalized(Motor::MAX_VOLTAGE),
+        )
+    }
+
+    /// Stop the drivetrain by commanding zero voltage to both sides.
+    pub fn stop(&mut self) -> Execute<'_, Voltages> {
+        self.execute(Voltages::default())
+    }
+
+    /// Drive at fixed voltages for a given `duration`, then stop.
+    ///
+    /// This is an open-loop motion. It doesn't use any sensor feedback, so the distance it covers will
+    /// vary with battery voltage, friction, and load. This makes it a useful fallback when sensors have
+    /// failed, but closed-loop commands should be preferred otherwise. See [`TimedVoltages`] for how
+    /// the duration is measured.
+    pub async fn drive_for(&mut self, voltages: Voltages, duration: Duration) {
+        self.execute(TimedVoltages::new(voltages, duration)).await;
+        self.stop().await;
+    }
+
+    /// Actively hold the robot's current pose for a given `duration`, then stop.
+    ///
+    /// This resists the robot being pushed by running a linear and angular controller against the
+    /// pose the robot was in when this was called. This is useful in endgame scenarios where the
+    /// robot needs to stay put (for example, while hanging or defending a position) rather than
+    /// coasting freely.
+    pub async fn hold_pose(
+        &mut self,
+        linear_controller: impl MotionController<Input = f64, Output = f64>,
+        angular_controller: impl MotionController<Input = f64, Output = f64>,
+        duration: Duration,
+    ) {
+        *self.command.lock().await = Box::new(HoldPose::new(linear_controller, angular_controller));
+        sleep(duration).await;
+        self.stop().await;
+    }
+
+    /// Invert the voltages commanded to one side of the drivetrain.
+    ///
+    /// This is a software fix for a side whose motors were wired or configured backwards. It only
+    /// affects commanded voltages. Tracking wheels and other sensors are not affected, and should
+    /// have their own direction configured separately.
+    pub fn set_inverted(&mut self, side: Side, inverted: bool) {
+        match side {
+            Side::Left => self.left_inverted.store(inverted, Ordering::Relaxed),
+            Side::Right => self.right_inverted.store(inverted, Ordering::Relaxed),
+        }
+    }
+
+    /// Returns `true` if commanded voltages to a side of the drivetrain are inverted.
+    pub fn is_inverted(&self, side: Side) -> bool {
+        match side {
+            Side::Left => self.left_inverted.load(Ordering::Relaxed),
+            Side::Right => self.right_inverted.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Capture a snapshot of the drivetrain's current state for diagnostics.
+    ///
+    /// Pose and forward travel are read from the drivetrain's [`Tracking`] implementation. Per-motor
+    /// readings are taken directly from the drive motors, and are `None` if a motor couldn't be read
+    /// (for example, if it's disconnected).
+    pub async fn debug_snapshot(&self) -> DrivetrainSnapshot {
+        // Tracking is read before the motors are locked, since tracking wheels may read the same motors.
+        let (position, heading, forward_travel) = {
+            let tracking = self.tracking.lock().await;
+            (
+                tracking.position(),
+                tracking.heading(),
+                tracking.forward_travel(),
+            )
+        };
+
+        DrivetrainSnapshot {
+            position,
+            heading,
+            forward_travel,
+            left_motors: self
+                .left_motors
+                .lock()
+                .await
+                .iter()
+                .map(MotorSnapshot::from)
+                .collect(),
+            right_motors: self
+                .right_motors
+                .lock()
+                .await
+                .iter()
+                .map(MotorSnapshot::from)
+                .collect(),
+        }
+    }
+
+    /// Prepare the drivetrain for the autonomous period.
+    ///
+    /// This replaces the running command with zero voltage (discarding any controller state the previous
+    /// command held) and sets the tracked pose to the robot's starting `position` and `heading`.
+    pub async fn reset_for_auto(&mut self, position: Vec2, heading: f64) {
+        *self.command.lock().await = Box::new(Voltages::default());
+
+        let mut tracking = self.tracking.lock().await;
+        tracking.set_position(position);
+        tracking.set_heading(heading);
+    }
+
+    /// Prepare the drivetrain for the driver control period.
+    ///
+    /// This replaces the running command (such as an unfinished autonomous motion) with zero voltage.
+    /// The tracked pose is intentionally left untouched, so it remains valid if it's needed later.
+    pub async fn reset_for_driver(&mut self) {
+        *self.command.lock().await = Box::new(Voltages::default());
+    }
+
+    /// Returns `true` if the drivetrain has persistently failed to command its motors.
+    ///
+    /// See [`FaultCounter`] for when a fault is reported and cleared.
+    pub fn is_faulted(&self) -> bool {
+        self.faults.is_faulted()
+    }
+
+    /// Set the maximum voltage that will be commanded to either side of the drivetrain.
+    ///
+    /// This applies to every command run on the drivetrain, which is useful for limiting current draw
+    /// on robots prone to brownouts. Commands may also limit their own output. In that case both limits
+    /// apply, so the lower of the two wins. Defaults to [`Motor::MAX_VOLTAGE`].
+    ///
+    /// Values that aren't positive and finite are ignored, and the previous maximum is kept.
+    pub fn set_max_voltage(&mut self, max_voltage: f64) {
+        if max_voltage.is_finite() && max_voltage > 0.0 {
+            self.max_voltage
+                .store(max_voltage.to_bits(), Ordering::Relaxed);
         }
     }
 
+    /// Get the maximum voltage that will be commanded to either side of the drivetrain.
+    pub fn max_voltage(&self) -> f64 {
+        f64::from_bits(self.max_voltage.load(Ordering::Relaxed))
+    }
+
     pub fn tracking(&self) -> Arc<Mutex<T>> {
         Arc::clone(&self.tracking)
     }
@@ -86,12 +279,104 @@ impl<T: Tracking> DifferentialDrivetrain<T> {
     }
 }
 
-pub enum Execute<'a, O: 'static> {
+impl<T: RotarySensor, U: RotarySensor> DifferentialDrivetrain<ParallelWheelTracking<T, U>> {
+    /// Measure the drivetrain's effective track width by turning in place.
+    ///
+    /// The robot spins counterclockwise at `voltage` until it has turned through `rotations` full turns,
+    /// then stops. The track width is computed from the difference in travel between the left and right
+    /// tracking wheels and the rotation measured by tracking (see [`tracking::track_width_from_spin`]).
+    /// The returned value can be used as the sum of the tracking wheels' offsets.
+    ///
+    /// The rotation has to be measured independently of the wheels being calibrated, so tracking must have
+    /// a gyro and must not use [`HeadingSource::WheelsOnly`](tracking::HeadingSource::WheelsOnly).
+    /// Otherwise, this just returns the track width tracking is already configured with. Several rotations
+    /// at a moderate voltage give the most consistent results, since they average out any error in where
+    /// the robot starts and stops.
+    pub async fn calibrate_track_width(&mut self, rotations: f64, voltage: f64) -> f64 {
+        let (start_left, start_right, start_rotation) = {
+            let tracking = self.tracking.lock().await;
+            (
+                tracking.left_travel(),
+                tracking.right_travel(),
+                tracking.total_rotation(),
+            )
+        };
+
+        self.execute(Voltages(-voltage, voltage)).await;
+        while (self.tracking.lock().await.total_rotation() - start_rotation).abs() < rotations * TAU
+        {
+            sleep(Motor::DATA_READ_INTERVAL).await;
+        }
+        self.stop().await;
+
+        let tracking = self.tracking.lock().await;
+        tracking::track_width_from_spin(
+            tracking.left_travel() - start_left,
+            tracking.right_travel() - start_right,
+            tracking.total_rotation() - start_rotation,
+        )
+    }
+}
+
+/// Limit a command's output to a drivetrain's maximum voltage, then invert the output of inverted sides.
+fn apply_limits(
+    voltages: Voltages,
+    max_voltage: f64,
+    left_inverted: bool,
+    right_inverted: bool,
+) -> Voltages {
+    let Voltages(left, right) = voltages.normalized(max_voltage);
+
+    Voltages(
+        if left_inverted { -left } else { left },
+        if right_inverted { -right } else { right },
+    )
+}
+
+/// A future that sets a drivetrain's command, then resolves once the command has settled.
+///
+/// The command's lock is only held briefly while it's set and each time it's checked, so the
+/// drivetrain's background task is free to update it in the meantime. If the command is replaced
+/// by another one before it settles, this future resolves immediately.
+///
+/// If this future is dropped before the command settles (for example, by returning early or losing
+/// a `select!`), the command is cancelled through [`Command::cancel`] so the drivetrain doesn't
+/// keep driving with whatever output it was last given.
+#[must_use = "the command is only set once this future is awaited"]
+pub struct Execute<'a, O: 'static> {
+    command: &'a Mutex<Box<dyn Command<Output = O>>>,
+    state: ExecuteState<'a, O>,
+    finished: bool,
+}
+
+impl<'a, O: 'static> Execute<'a, O> {
+    fn new(
+        command: &'a Mutex<Box<dyn Command<Output = O>>>,
+        new_command: Box<dyn Command<Output = O>>,
+    ) -> Self {
+        Self {
+            command,
+            state: ExecuteState::SetCommand {
+                guard: command.lock(),
+                command: new_command,
+            },
+            finished: false,
+        }
+    }
+}
+
+enum ExecuteState<'a, O: 'static> {
     SetCommand {
         guard: MutexLockFuture<'a, Box<dyn Command<Output = O>>>,
         command: Box<dyn Command<Output = O>>,
     },
-    Settle(MutexGuard<'a, Box<dyn Command<Output = O>>>),
+    /// Waiting for the command with the given [`command_id`] to settle.
+    Settle(usize),
+}
+
+/// Identifies a boxed command by its address, so `Execute` can tell if it has been replaced.
+fn command_id<O>(command: &dyn Command<Output = O>) -> usize {
+    command as *const dyn Command<Output = O> as *const () as usize
 }
 
 impl<'a, O: 'static> Future for Execute<'a, O> {
@@ -99,29 +384,35 @@ impl<'a, O: 'static> Future for Execute<'a, O> {
 
     fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let mut is_settled = false;
+        let mutex = self.command;
 
         replace_with(
-            &mut *self,
+            &mut self.state,
             || panic!("Failed to replace"),
-            |self_| match self_ {
-                Self::SetCommand { mut guard, command } => match pin!(&mut guard).poll(cx) {
-                    Poll::Ready(mut lock) => {
-                        *lock = command;
-                        Self::Settle(lock)
+            |state| match state {
+                ExecuteState::SetCommand { mut guard, command } => {
+                    match pin!(&mut guard).poll(cx) {
+                        Poll::Ready(mut lock) => {
+                            *lock = command;
+                            ExecuteState::Settle(command_id(&**lock))
+                        }
+                        Poll::Pending => ExecuteState::SetCommand { guard, command },
                     }
-                    Poll::Pending => Self::SetCommand { guard, command },
-                },
-                Self::Settle(lock) => {
-                    if lock.is_settled() {
-                        is_settled = true;
+                }
+                ExecuteState::Settle(id) => {
+                    if let Some(lock) = mutex.try_lock() {
+                        if command_id(&**lock) != id || lock.is_settled() {
+                            is_settled = true;
+                        }
                     }
 
-                    Self::Settle(lock)
+                    ExecuteState::Settle(id)
                 }
             },
         );
 
         if is_settled {
+            self.finished = true;
             Poll::Ready(())
         } else {
             cx.waker().wake_by_ref();
@@ -130,17 +421,145 @@ impl<'a, O: 'static> Future for Execute<'a, O> {
     }
 }
 
+impl<'a, O: 'static> Drop for Execute<'a, O> {
+    fn drop(&mut self) {
+        // Only commands that were actually set, haven't settled, and haven't been replaced need to be
+        // cancelled.
+        if let ExecuteState::Settle(id) = self.state {
+            if !self.finished {
+                if let Some(mut lock) = self.command.try_lock() {
+                    if command_id(&**lock) == id {
+                        lock.cancel();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Diagnostic readings from a single drive motor.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct MotorSnapshot {
+    /// Velocity in RPM.
+    pub velocity: Option<f64>,
+
+    /// Temperature in degrees Celsius.
+    pub temperature: Option<f64>,
+
+    /// Current draw in amps.
+    pub current: Option<f64>,
+}
+
+impl From<&Motor> for MotorSnapshot {
+    fn from(motor: &Motor) -> Self {
+        Self {
+            velocity: motor.velocity().ok().map(f64::from),
+            temperature: motor.temperature().ok(),
+            current: motor.current().ok(),
+        }
+    }
+}
+
+fn fmt_reading(
+    f: &mut fmt::Formatter,
+    name: &str,
+    reading: Option<f64>,
+    unit: &str,
+) -> fmt::Result {
+    match reading {
+        Some(reading) => write!(f, "{name}: {reading:.2}{unit}"),
+        None => write!(f, "{name}: N/A"),
+    }
+}
+
+impl fmt::Display for MotorSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_reading(f, "velocity", self.velocity, "rpm")?;
+        write!(f, ", ")?;
+        fmt_reading(f, "temperature", self.temperature, "°C")?;
+        write!(f, ", ")?;
+        fmt_reading(f, "current", self.current, "A")
+    }
+}
+
+/// A snapshot of a [`DifferentialDrivetrain`]'s state, returned by [`DifferentialDrivetrain::debug_snapshot`].
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct DrivetrainSnapshot {
+    /// The robot's position, as reported by [`Tracking::position`].
+    pub position: Vec2,
+
+    /// The robot's heading in radians, as reported by [`Tracking::heading`].
+    pub heading: f64,
+
+    /// The robot's forward travel, as reported by [`Tracking::forward_travel`].
+    pub forward_travel: f64,
+
+    /// Readings from each motor on the left side of the drivetrain.
+    pub left_motors: Vec<MotorSnapshot>,
+
+    /// Readings from each motor on the right side of the drivetrain.
+    pub right_motors: Vec<MotorSnapshot>,
+}
+
+impl fmt::Display for DrivetrainSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "position: {}", self.position)?;
+        writeln!(f, "heading: {:.4} rad", self.heading)?;
+        write!(f, "forward travel: {:.4}", self.forward_travel)?;
+
+        for (i, motor) in self.left_motors.iter().enumerate() {
+            write!(f, "\nleft motor {i}: {motor}")?;
+        }
+        for (i, motor) in self.right_motors.iter().enumerate() {
+            write!(f, "\nright motor {i}: {motor}")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Left/Right Motor Voltages
-/// 
+///
 /// Used as the standard output of a [`Command`] when working with the [`DifferentialDrivetrain`]
 /// struct.
 ///
 /// This struct is additionally a [`Command`] in itself, and can be used to run a drivetrain at a
 /// fixed voltage.
+///
+/// # Turning Convention
+///
+/// Headings are measured counterclockwise, so commands that combine a linear output with an angular
+/// output (such as a correction for `target_heading - heading`) treat a positive angular output as a
+/// counterclockwise turn, slowing the left side and speeding up the right side:
+/// `Voltages(linear - angular, linear + angular)`.
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub struct Voltages(pub f64, pub f64);
 
 impl Voltages {
+    /// Compute left and right voltages from an arcade-style `throttle` and `turn` command.
+    ///
+    /// Positive `turn` values turn the robot clockwise (right). The resulting voltages may exceed
+    /// the motors' maximum voltage, and should be [normalized](Voltages::normalized) before use.
+    pub fn from_arcade(throttle: f64, turn: f64) -> Self {
+        Self(throttle + turn, throttle - turn)
+    }
+
+    /// Compute left and right voltages from a linear and angular output, preserving the angular
+    /// output under saturation.
+    ///
+    /// Rather than scaling both outputs down equally like [`Voltages::normalized`], the angular output
+    /// is first limited to `max`, then the linear output is limited to whatever headroom remains. This
+    /// ensures that a saturated linear output can never take away turning authority.
+    ///
+    /// Positive `angular` values turn the robot counterclockwise.
+    pub fn prioritized(linear: f64, angular: f64, max: f64) -> Self {
+        let angular = angular.clamp(-max, max);
+        let headroom = max - angular.abs();
+        let linear = linear.clamp(-headroom, headroom);
+
+        Self(linear - angular, linear + angular)
+    }
+
     /// Normalizes the ratio of voltages between two motors.
     ///
     /// If either motor is over a `max_voltage`, limit both voltages to preserve
@@ -176,5 +595,179 @@ impl Command for Voltages {
         true
     }
 
-    fn cancel(&mut self) {}
-}
\ No newline at end of file
+    fn cancel(&mut self) {
+        *self = Self::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, vec};
+
+    use super::*;
+
+    #[test]
+    fn max_voltage_limits_output() {
+        assert_eq!(
+            apply_limits(Voltages(12.0, 6.0), 8.0, false, false),
+            Voltages(8.0, 4.0)
+        );
+        assert_eq!(
+            apply_limits(Voltages(4.0, -2.0), 8.0, false, false),
+            Voltages(4.0, -2.0)
+        );
+    }
+
+    #[test]
+    fn inverting_one_side_negates_only_that_side() {
+        let voltages = Voltages(6.0, 4.0);
+
+        assert_eq!(
+            apply_limits(voltages, Motor::MAX_VOLTAGE, true, false),
+            Voltages(-6.0, 4.0)
+        );
+        assert_eq!(
+            apply_limits(voltages, Motor::MAX_VOLTAGE, false, true),
+            Voltages(6.0, -4.0)
+        );
+    }
+
+    #[test]
+    fn arcade_voltages() {
+        let max = Motor::MAX_VOLTAGE;
+
+        assert_eq!(
+            Voltages::from_arcade(max, 0.0).normalized(max),
+            Voltages(max, max)
+        );
+        assert_eq!(
+            Voltages::from_arcade(0.0, max).normalized(max),
+            Voltages(max, -max)
+        );
+
+        // Saturated inputs are scaled down while preserving the ratio between sides.
+        let Voltages(left, right) = Voltages::from_arcade(max, max / 2.0).normalized(max);
+        assert_eq!(left, max);
+        assert!((right - max / 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn prioritized_preserves_angular_output() {
+        let max = Motor::MAX_VOLTAGE;
+
+        // Both outputs saturated: angular keeps its full value, linear gets the remaining headroom.
+        assert_eq!(Voltages::prioritized(max, 4.0, max), Voltages(4.0, max));
+
+        // Angular alone beyond the limit is clamped, leaving no room for linear output.
+        assert_eq!(
+            Voltages::prioritized(max, 2.0 * max, max),
+            Voltages(-max, max)
+        );
+
+        // Unsaturated outputs pass through unchanged.
+        assert_eq!(Voltages::prioritized(3.0, 1.0, max), Voltages(2.0, 4.0));
+    }
+
+    #[test]
+    fn snapshot_display() {
+        let snapshot = DrivetrainSnapshot {
+            position: Vec2::new(1.0, 2.5),
+            heading: 0.5,
+            forward_travel: 12.0,
+            left_motors: vec![MotorSnapshot {
+                velocity: Some(200.0),
+                temperature: Some(35.0),
+                current: Some(1.25),
+            }],
+            right_motors: vec![MotorSnapshot::default()],
+        };
+
+        assert_eq!(
+            format!("{snapshot}"),
+            format!(
+                "position: {}\nheading: 0.5000 rad\nforward travel: 12.0000\n\
+                 left motor 0: velocity: 200.00rpm, temperature: 35.00°C, current: 1.25A\n\
+                 right motor 0: velocity: N/A, temperature: N/A, current: N/A",
+                Vec2::new(1.0, 2.5)
+            )
+        );
+    }
+
+    /// A command that settles on request and counts how many times it was cancelled.
+    #[derive(Debug, Default)]
+    struct MockCommand {
+        settled: Arc<AtomicBool>,
+        cancels: Arc<AtomicU64>,
+    }
+
+    impl Command for MockCommand {
+        type Output = Voltages;
+
+        fn update(&mut self, _ctx: TrackingContext) -> Self::Output {
+            Voltages(6.0, 6.0)
+        }
+
+        fn is_settled(&self) -> bool {
+            self.settled.load(Ordering::Relaxed)
+        }
+
+        fn cancel(&mut self) {
+            self.cancels.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn poll(future: Pin<&mut Execute<Voltages>>) -> Poll<()> {
+        future.poll(&mut Context::from_waker(core::task::Waker::noop()))
+    }
+
+    #[test]
+    fn dropping_unfinished_execute_cancels_command() {
+        let command = MockCommand::default();
+        let cancels = Arc::clone(&command.cancels);
+        let mutex: Mutex<Box<dyn Command<Output = Voltages>>> =
+            Mutex::new(Box::new(Voltages::default()));
+
+        let mut future = Box::pin(Execute::new(&mutex, Box::new(command)));
+        assert_eq!(poll(future.as_mut()), Poll::Pending);
+        assert_eq!(poll(future.as_mut()), Poll::Pending);
+
+        drop(future);
+        assert_eq!(cancels.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn dropping_finished_execute_does_not_cancel_command() {
+        let command = MockCommand::default();
+        let (settled, cancels) = (Arc::clone(&command.settled), Arc::clone(&command.cancels));
+        let mutex: Mutex<Box<dyn Command<Output = Voltages>>> =
+            Mutex::new(Box::new(Voltages::default()));
+
+        let mut future = Box::pin(Execute::new(&mutex, Box::new(command)));
+        assert_eq!(poll(future.as_mut()), Poll::Pending);
+
+        settled.store(true, Ordering::Relaxed);
+        assert_eq!(poll(future.as_mut()), Poll::Ready(()));
+
+        drop(future);
+        assert_eq!(cancels.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn dropping_execute_after_replacement_does_not_cancel_new_command() {
+        let (first, second) = (MockCommand::default(), MockCommand::default());
+        let (first_cancels, second_cancels) =
+            (Arc::clone(&first.cancels), Arc::clone(&second.cancels));
+        let mutex: Mutex<Box<dyn Command<Output = Voltages>>> =
+            Mutex::new(Box::new(Voltages::default()));
+
+        let mut future = Box::pin(Execute::new(&mutex, Box::new(first)));
+        assert_eq!(poll(future.as_mut()), Poll::Pending);
+
+        // Another motion replaces the command before the first one settles.
+        *mutex.try_lock().unwrap() = Box::new(second);
+
+        drop(future);
+        assert_eq!(first_cancels.load(Ordering::Relaxed), 0);
+        assert_eq!(second_cancels.load(Ordering::Relaxed), 0);
+    }
+}
@@ -0,0 +1,101 @@
+//! Fast polynomial approximations of trigonometric functions.
+//!
+//! These trade a small amount of accuracy for speed, and are used in place of `libm` in the
+//! tracking hot path when the `fast-math` feature is enabled. The feature is off by default.
+//!
+//! - [`sin`] and [`cos`] are accurate to within `4e-6` for angles within ±10⁶ radians. Accuracy degrades
+//!   for larger angles, since reducing them into one period loses precision.
+//! - [`atan2`] is accurate to within `2e-5` radians.
+
+use core::f64::consts::{FRAC_PI_2, PI, TAU};
+use num_traits::real::Real;
+
+/// Approximate the sine of an angle in radians.
+pub fn sin(angle: f64) -> f64 {
+    // Reduce to [-π, π], then fold into [-π/2, π/2] using sin(x) = sin(π - x).
+    let mut x = angle - TAU * (angle / TAU).round();
+
+    if x > FRAC_PI_2 {
+        x = PI - x;
+    } else if x < -FRAC_PI_2 {
+        x = -PI - x;
+    }
+
+    // 9th order Taylor polynomial, evaluated using Horner's method.
+    let x2 = x * x;
+
+    x * (1.0
+        + x2 * (-1.0 / 6.0 + x2 * (1.0 / 120.0 + x2 * (-1.0 / 5040.0 + x2 * (1.0 / 362880.0)))))
+}
+
+/// Approximate the cosine of an angle in radians.
+pub fn cos(angle: f64) -> f64 {
+    sin(angle + FRAC_PI_2)
+}
+
+/// Approximate the sine and cosine of an angle in radians.
+pub fn sin_cos(angle: f64) -> (f64, f64) {
+    (sin(angle), cos(angle))
+}
+
+/// Approximate the four-quadrant arctangent of `y / x` in radians.
+pub fn atan2(y: f64, x: f64) -> f64 {
+    if x == 0.0 && y == 0.0 {
+        return 0.0;
+    }
+
+    let (abs_x, abs_y) = (x.abs(), y.abs());
+
+    // Approximate atan on [0, 1] (Abramowitz & Stegun 4.4.49), then unfold into the full circle.
+    let z = abs_x.min(abs_y) / abs_x.max(abs_y);
+    let z2 = z * z;
+    let mut angle =
+        z * (0.9998660 + z2 * (-0.3302995 + z2 * (0.1801410 + z2 * (-0.0851330 + z2 * 0.0208351))));
+
+    if abs_y > abs_x {
+        angle = FRAC_PI_2 - angle;
+    }
+    if x < 0.0 {
+        angle = PI - angle;
+    }
+    if y < 0.0 {
+        angle = -angle;
+    }
+
+    angle
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sin_cos_match_libm() {
+        for i in -100_000..=100_000 {
+            let angle = i as f64 * 0.01;
+
+            assert!((sin(angle) - Real::sin(angle)).abs() < 4e-6);
+            assert!((cos(angle) - Real::cos(angle)).abs() < 4e-6);
+        }
+
+        for angle in [-1e6, -12345.678, 98765.4321, 1e6] {
+            assert!((sin(angle) - Real::sin(angle)).abs() < 4e-6);
+            assert!((cos(angle) - Real::cos(angle)).abs() < 4e-6);
+        }
+    }
+
+    #[test]
+    fn atan2_matches_libm() {
+        for i in -50..=50 {
+            for j in -50..=50 {
+                let (y, x) = (i as f64 * 0.37, j as f64 * 0.41);
+
+                if x == 0.0 && y == 0.0 {
+                    assert_eq!(atan2(y, x), 0.0);
+                } else {
+                    assert!((atan2(y, x) - Real::atan2(y, x)).abs() < 2e-5);
+                }
+            }
+        }
+    }
+}
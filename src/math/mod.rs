@@ -1,13 +1,28 @@
+pub mod potential_field;
 pub mod vec2;
 
+#[cfg(feature = "fast-math")]
+pub mod fast;
+
 pub use vec2::Vec2;
 
-use core::f64::consts::FRAC_2_PI;
+use core::f64::consts::{PI, TAU};
 
 /// Constrain an angle in radians from -π to +π.
 ///
 /// This preserves the angle's direction while keeping it within minimum constrains,
 /// allowing certain operations to be performed easier.
 pub fn normalize_angle(angle: f64) -> f64 {
-    angle % FRAC_2_PI
+    angle - TAU * num_traits::real::Real::floor((angle + PI) / TAU)
+}
+
+/// Compute the sine and cosine of an angle in radians.
+///
+/// This uses the approximations in [`fast`] if the `fast-math` feature is enabled, and `libm` otherwise.
+pub(crate) fn sin_cos(angle: f64) -> (f64, f64) {
+    #[cfg(feature = "fast-math")]
+    return fast::sin_cos(angle);
+
+    #[cfg(not(feature = "fast-math"))]
+    return num_traits::real::Real::sin_cos(angle);
 }
@@ -0,0 +1,124 @@
+use alloc::vec::Vec;
+use num_traits::real::Real;
+
+use super::Vec2;
+
+/// A circular obstacle that repels a [`PotentialField`].
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Obstacle {
+    /// The center of the obstacle.
+    pub position: Vec2,
+
+    /// The distance from the obstacle's center at which it begins repelling the robot.
+    pub influence_radius: f64,
+}
+
+impl Obstacle {
+    /// Construct a new [`Obstacle`] from a position and radius of influence.
+    pub const fn new(position: Vec2, influence_radius: f64) -> Self {
+        Self {
+            position,
+            influence_radius,
+        }
+    }
+}
+
+/// A simple reactive steering helper using artificial potential fields.
+///
+/// The goal attracts the robot with a constant-magnitude vector, while each [`Obstacle`] within its
+/// radius of influence repels the robot with a vector that grows rapidly as the robot gets closer. The
+/// sum of these vectors gives a direction to steer in, which can be used as a target direction each update.
+///
+/// This is a lightweight alternative to path planning, but it has no global knowledge of the field. In
+/// particular, the robot can get stuck in *local minima* where the attractive and repulsive vectors cancel
+/// out (for example, directly behind an obstacle lined up with the goal).
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct PotentialField {
+    /// The point the robot is attracted to.
+    pub goal: Vec2,
+
+    /// Obstacles repelling the robot.
+    pub obstacles: Vec<Obstacle>,
+
+    /// The magnitude of the goal's attraction.
+    pub attractive_gain: f64,
+
+    /// Scales the strength of each obstacle's repulsion.
+    pub repulsive_gain: f64,
+}
+
+impl PotentialField {
+    /// Construct a new [`PotentialField`].
+    pub fn new(
+        goal: Vec2,
+        obstacles: impl Into<Vec<Obstacle>>,
+        attractive_gain: f64,
+        repulsive_gain: f64,
+    ) -> Self {
+        Self {
+            goal,
+            obstacles: obstacles.into(),
+            attractive_gain,
+            repulsive_gain,
+        }
+    }
+
+    /// Compute the steering vector at a given position.
+    pub fn steering(&self, position: Vec2) -> Vec2 {
+        let to_goal = self.goal - position;
+
+        let mut steering = if to_goal.length() > 0.0 {
+            to_goal.unit() * self.attractive_gain
+        } else {
+            Vec2::default()
+        };
+
+        for obstacle in &self.obstacles {
+            let away = position - obstacle.position;
+            let distance = away.length();
+
+            if distance > 0.0 && distance < obstacle.influence_radius {
+                let magnitude = self.repulsive_gain
+                    * (1.0 / distance - 1.0 / obstacle.influence_radius)
+                    / distance.powi(2);
+
+                steering += away.unit() * magnitude;
+            }
+        }
+
+        steering
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steers_toward_goal_without_obstacles() {
+        let field = PotentialField::new(Vec2::new(10.0, 0.0), [], 2.0, 1.0);
+
+        assert_eq!(field.steering(Vec2::new(0.0, 0.0)), Vec2::new(2.0, 0.0));
+        assert_eq!(field.steering(Vec2::new(10.0, 0.0)), Vec2::default());
+    }
+
+    #[test]
+    fn obstacle_deflects_steering() {
+        // Obstacle slightly to the left of the straight line between the robot and goal.
+        let obstacle = Obstacle::new(Vec2::new(2.0, 0.5), 3.0);
+        let field = PotentialField::new(Vec2::new(10.0, 0.0), [obstacle], 1.0, 1.0);
+
+        let steering = field.steering(Vec2::new(0.0, 0.0));
+
+        assert!(steering.y < 0.0);
+        assert!(steering.x < 1.0);
+    }
+
+    #[test]
+    fn distant_obstacle_is_ignored() {
+        let obstacle = Obstacle::new(Vec2::new(5.0, 5.0), 1.0);
+        let field = PotentialField::new(Vec2::new(10.0, 0.0), [obstacle], 1.0, 1.0);
+
+        assert_eq!(field.steering(Vec2::new(0.0, 0.0)), Vec2::new(1.0, 0.0));
+    }
+}
@@ -1,3 +1,5 @@
+#[cfg(feature = "approx")]
+use approx::{AbsDiffEq, RelativeEq};
 use core::{fmt, ops};
 use num_traits::real::Real;
 
@@ -29,6 +31,11 @@ impl Vec2 {
         self.y
     }
 
+    /// Returns `true` if neither component of the vector is NaN or infinite.
+    pub fn is_finite(&self) -> bool {
+        self.x.is_finite() && self.y.is_finite()
+    }
+
     /// Construct a `Vec2` from polar coordinates.
     pub fn from_polar(r: f64, theta: f64) -> Self {
         let (sin, cos) = theta.sin_cos();
@@ -39,6 +46,14 @@ impl Vec2 {
         }
     }
 
+    /// Convert this vector into polar coordinates, returning its magnitude and angle in radians.
+    ///
+    /// The angle is measured in standard position (counterclockwise-positive from the +x axis) in the
+    /// range [-π, π]. This is the inverse of [`Vec2::from_polar`]. The zero vector converts to `(0.0, 0.0)`.
+    pub fn to_polar(&self) -> (f64, f64) {
+        (self.length(), self.angle())
+    }
+
     /// Determine this vector's angle in radians relative to the origin (0, 0).
     pub fn angle(&self) -> f64 {
         self.y.atan2(self.x)
@@ -58,6 +73,27 @@ impl Vec2 {
         self + ((other - self) * t)
     }
 
+    /// Compute the point halfway between two vectors.
+    pub fn midpoint(a: Vec2, b: Vec2) -> Vec2 {
+        (a + b) / 2.0
+    }
+
+    /// Compute the centroid (average) of a set of points.
+    ///
+    /// Returns `None` if `points` is empty, since an empty set of points has no meaningful center
+    /// (defaulting to the origin would silently hide the mistake).
+    pub fn centroid(points: &[Vec2]) -> Option<Vec2> {
+        if points.is_empty() {
+            return None;
+        }
+
+        let sum = points
+            .iter()
+            .fold(Vec2::default(), |sum, point| sum + *point);
+
+        Some(sum / points.len() as f64)
+    }
+
     /// Compute the dot product between this vector and another `Vec2`.
     ///
     /// The dot product is the sum of the products of each vector's components,
@@ -72,6 +108,16 @@ impl Vec2 {
         self.x * other.y - self.y * other.x
     }
 
+    /// Get the vector perpendicular to this one, rotated 90 degrees counterclockwise.
+    ///
+    /// For a vector `(x, y)`, this is `(-y, x)`.
+    pub fn perp(&self) -> Self {
+        Self {
+            x: -self.y,
+            y: self.x,
+        }
+    }
+
     /// Get the unit (normalized) vector.
     ///
     /// This function creates a `Vec2` with a length of 1.0 while retaining the
@@ -97,6 +143,37 @@ impl Vec2 {
     }
 }
 
+#[cfg(feature = "approx")]
+impl AbsDiffEq for Vec2 {
+    type Epsilon = f64;
+
+    fn default_epsilon() -> Self::Epsilon {
+        f64::default_epsilon()
+    }
+
+    fn abs_diff_eq(&self, other: &Self, epsilon: Self::Epsilon) -> bool {
+        self.x.abs_diff_eq(&other.x, epsilon) && self.y.abs_diff_eq(&other.y, epsilon)
+    }
+}
+
+#[cfg(feature = "approx")]
+impl RelativeEq for Vec2 {
+    fn default_max_relative() -> Self::Epsilon {
+        f64::default_max_relative()
+    }
+
+    fn relative_eq(
+        &self,
+        other: &Self,
+        epsilon: Self::Epsilon,
+        max_relative: Self::Epsilon,
+    ) -> bool {
+        self.x.relative_eq(&other.x, epsilon, max_relative)
+            && self.y.relative_eq(&other.y, epsilon, max_relative)
+    }
+}
+
+/// Converts an `(x, y)` tuple into a [`Vec2`].
 impl From<(f64, f64)> for Vec2 {
     fn from(tuple: (f64, f64)) -> Self {
         Self {
@@ -106,6 +183,30 @@ impl From<(f64, f64)> for Vec2 {
     }
 }
 
+/// Converts an `[x, y]` array into a [`Vec2`].
+impl From<[f64; 2]> for Vec2 {
+    fn from(array: [f64; 2]) -> Self {
+        Self {
+            x: array[0],
+            y: array[1],
+        }
+    }
+}
+
+/// Converts a [`Vec2`] into an `(x, y)` tuple.
+impl From<Vec2> for (f64, f64) {
+    fn from(vec: Vec2) -> Self {
+        (vec.x, vec.y)
+    }
+}
+
+/// Converts a [`Vec2`] into an `[x, y]` array.
+impl From<Vec2> for [f64; 2] {
+    fn from(vec: Vec2) -> Self {
+        [vec.x, vec.y]
+    }
+}
+
 impl fmt::Display for Vec2 {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "({}, {})", self.x, self.y)
@@ -167,6 +268,14 @@ impl ops::Mul<f64> for Vec2 {
     }
 }
 
+impl ops::Mul<Vec2> for f64 {
+    type Output = Vec2;
+
+    fn mul(self, vec: Vec2) -> Vec2 {
+        vec * self
+    }
+}
+
 impl ops::Div<f64> for Vec2 {
     type Output = Self;
 
@@ -230,3 +339,92 @@ impl ops::DivAssign<f64> for Vec2 {
         self.y /= scalar;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn polar_round_trip() {
+        for vec in [
+            Vec2::new(3.0, 4.0),
+            Vec2::new(-2.0, 0.5),
+            Vec2::new(0.0, -7.0),
+            Vec2::new(0.0, 0.0),
+        ] {
+            let (r, theta) = vec.to_polar();
+            let round_trip = Vec2::from_polar(r, theta);
+
+            assert!((round_trip - vec).length() < 1e-12);
+        }
+
+        assert_eq!(Vec2::default().to_polar(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn scalar_multiplication_commutes() {
+        let vec = Vec2::new(1.5, -2.0);
+
+        assert_eq!(2.0 * vec, vec * 2.0);
+        assert_eq!(2.0 * vec, Vec2::new(3.0, -4.0));
+        assert_eq!(-0.5 * vec, vec * -0.5);
+    }
+
+    #[test]
+    fn midpoint_and_centroid() {
+        assert_eq!(
+            Vec2::midpoint(Vec2::new(0.0, 0.0), Vec2::new(4.0, -2.0)),
+            Vec2::new(2.0, -1.0)
+        );
+
+        assert_eq!(
+            Vec2::centroid(&[
+                Vec2::new(0.0, 0.0),
+                Vec2::new(6.0, 0.0),
+                Vec2::new(0.0, 3.0)
+            ]),
+            Some(Vec2::new(2.0, 1.0))
+        );
+        assert_eq!(Vec2::centroid(&[]), None);
+    }
+
+    #[test]
+    fn perp_and_dot() {
+        assert_eq!(Vec2::new(1.0, 0.0).perp(), Vec2::new(0.0, 1.0));
+
+        let vec = Vec2::new(3.0, -5.0);
+        assert_eq!(vec.dot(vec.perp()), 0.0);
+        assert_eq!(vec.dot(Vec2::new(2.0, 1.0)), 1.0);
+    }
+
+    #[cfg(feature = "approx")]
+    #[test]
+    fn approx_comparisons() {
+        use approx::{assert_abs_diff_eq, assert_relative_eq, assert_relative_ne};
+
+        let a = Vec2::new(1.0, 2.0);
+        let b = Vec2::new(1.0 + 1e-9, 2.0 - 1e-9);
+
+        assert_relative_eq!(a, b, epsilon = 1e-6);
+        assert_abs_diff_eq!(a, b, epsilon = 1e-6);
+        assert_relative_ne!(a, Vec2::new(1.1, 2.0), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn finiteness() {
+        assert!(Vec2::new(1.0, -2.0).is_finite());
+        assert!(!Vec2::new(f64::NAN, 0.0).is_finite());
+        assert!(!Vec2::new(0.0, f64::INFINITY).is_finite());
+    }
+
+    #[test]
+    fn tuple_and_array_round_trip() {
+        let vec = Vec2::from((1.5, -2.5));
+        assert_eq!(vec, Vec2::new(1.5, -2.5));
+        assert_eq!(<(f64, f64)>::from(vec), (1.5, -2.5));
+
+        let vec: Vec2 = [3.0, 4.0].into();
+        assert_eq!(vec, Vec2::new(3.0, 4.0));
+        assert_eq!(<[f64; 2]>::from(vec), [3.0, 4.0]);
+    }
+}
@@ -1,10 +1,15 @@
-use core::f64::consts::{FRAC_2_PI, PI};
-use core::fmt::Debug;
+use alloc::vec::Vec;
+use core::cell::Cell;
+use core::f64::consts::PI;
+use core::fmt::{self, Debug};
 use core::prelude::rust_2021::*;
-use num_traits::real::Real;
-use vexide::devices::smart::InertialSensor;
+use core::time::Duration;
+use vexide::devices::smart::{InertialSensor, Motor};
 
-use crate::{devices::RotarySensor, math::Vec2};
+use crate::{
+    devices::RotarySensor,
+    math::{self, Vec2},
+};
 
 /// A system that performs localization and returns telemetry on a mobile robot.
 pub trait Tracking: Send + 'static {
@@ -26,6 +31,59 @@ pub struct TrackingContext {
     pub forward_travel: f64,
 }
 
+/// Integrate a new forward travel and heading measurement into a previous tracking state.
+///
+/// This performs the arc-based odometry used by [`ParallelWheelTracking`]. It has no dependency on any
+/// hardware, so it can be run against synthetic measurements.
+///
+/// The robot is assumed to have driven along a circular arc between the two measurements. When the heading
+/// hasn't changed, that arc's radius is infinite, so the motion is treated as a straight line rather than
+/// clamping the change in heading to some small epsilon. This keeps the result exact and finite.
+pub fn step(prev: TrackingContext, forward_travel: f64, heading: f64) -> TrackingContext {
+    let delta_forward_travel = forward_travel - prev.forward_travel;
+    // Headings wrap around at ±π, so the change in heading is taken along the shortest arc.
+    let delta_heading = math::normalize_angle(heading - prev.heading);
+
+    // Find a position delta.
+    // This is a vector relative to the previous position, and can be found by creating a vector with our
+    // average forward travel as the y-axis, then rotating the y-axis about our current heading. This gives
+    // a rough estimate of the change in position, but does not account for sideways motion.
+    //
+    // If our heading hasn't changed, the robot moved in a straight line and the arc's chord length is just
+    // our forward travel. Dividing by a zero heading delta here would otherwise poison our position with NaN.
+    let chord_length = if delta_heading == 0.0 {
+        delta_forward_travel
+    } else {
+        2.0 * (delta_forward_travel / delta_heading) * math::sin_cos(delta_heading / 2.0).0
+    };
+
+    let (sin, cos) = math::sin_cos(prev.heading + (delta_heading / 2.0));
+
+    TrackingContext {
+        position: prev.position + Vec2::new(chord_length * cos, chord_length * sin),
+        heading,
+        forward_travel,
+    }
+}
+
+/// Compute a robot's effective track width from a turn in place.
+///
+/// When a differential robot turns in place by `rotation` radians, its wheels travel along circles whose
+/// circumferences differ by the track width, so the difference in travel between the right and left wheels
+/// is `rotation * track_width`. `rotation` should be measured independently of the wheels (for example,
+/// by an IMU), and is positive for counterclockwise turns.
+///
+/// Wheel scrub makes the effective track width differ from the physical distance between the wheels,
+/// so measuring it this way gives more accurate odometry than a tape measure. Returns NaN if `rotation`
+/// is zero.
+pub fn track_width_from_spin(left_travel: f64, right_travel: f64, rotation: f64) -> f64 {
+    if rotation == 0.0 {
+        return f64::NAN;
+    }
+
+    (right_travel - left_travel) / rotation
+}
+
 /// A struct representing a wheel attached to a rotary sensor.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TrackingWheel<T: RotarySensor> {
@@ -33,42 +91,137 @@ pub struct TrackingWheel<T: RotarySensor> {
     pub wheel_diameter: f64,
     pub offset: f64,
     pub gearing: Option<f64>,
+    last_travel: Cell<f64>,
 }
 
+/// An error returned when constructing a [`TrackingWheel`] with invalid measurements.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrackingWheelError {
+    /// The wheel's diameter was zero, negative, or not a number.
+    InvalidDiameter(f64),
+
+    /// The wheel's gearing ratio was zero or not a number.
+    InvalidGearing(f64),
+}
+
+impl fmt::Display for TrackingWheelError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidDiameter(diameter) => write!(
+                f,
+                "tracking wheel diameter must be a positive number, but was {diameter}"
+            ),
+            Self::InvalidGearing(gearing) => write!(
+                f,
+                "tracking wheel gearing must be a nonzero number, but was {gearing}"
+            ),
+        }
+    }
+}
+
+impl core::error::Error for TrackingWheelError {}
+
 impl<T: RotarySensor> TrackingWheel<T> {
+    /// Construct a new [`TrackingWheel`].
+    ///
+    /// In debug builds, this panics if `wheel_diameter` or `gearing` are invalid. Use
+    /// [`TrackingWheel::try_new`] to handle these cases gracefully.
     pub fn new(sensor: T, wheel_diameter: f64, offset: f64, gearing: Option<f64>) -> Self {
+        debug_assert!(
+            wheel_diameter > 0.0,
+            "tracking wheel diameter must be positive"
+        );
+        debug_assert!(
+            gearing.is_none_or(|gearing| gearing != 0.0 && !gearing.is_nan()),
+            "tracking wheel gearing must be nonzero"
+        );
+
         Self {
             sensor,
             wheel_diameter,
             offset,
             gearing,
+            last_travel: Cell::new(0.0),
         }
     }
+
+    /// Construct a new [`TrackingWheel`], returning an error if its measurements are invalid.
+    ///
+    /// A non-positive `wheel_diameter` or a zero `gearing` would cause the wheel to always report
+    /// zero (or negated) travel, which is almost always a configuration mistake.
+    pub fn try_new(
+        sensor: T,
+        wheel_diameter: f64,
+        offset: f64,
+        gearing: Option<f64>,
+    ) -> Result<Self, TrackingWheelError> {
+        if wheel_diameter <= 0.0 || wheel_diameter.is_nan() {
+            return Err(TrackingWheelError::InvalidDiameter(wheel_diameter));
+        }
+
+        if let Some(gearing) = gearing {
+            if gearing == 0.0 || gearing.is_nan() {
+                return Err(TrackingWheelError::InvalidGearing(gearing));
+            }
+        }
+
+        Ok(Self::new(sensor, wheel_diameter, offset, gearing))
+    }
 }
 
 impl<T: RotarySensor> TrackingWheel<T> {
+    /// Compute the distance this wheel has traveled.
+    ///
+    /// If the sensor can't be read (for example, if it's currently locked by another task), the last
+    /// successfully measured travel is returned instead.
     fn travel(&self) -> f64 {
         let wheel_circumference = self.wheel_diameter * PI;
 
-        return self
-            .sensor
-            .position()
-            .unwrap_or_default()
-            .as_revolutions()
-            * self.gearing.unwrap_or(1.0)
-            * wheel_circumference;
+        if let Ok(position) = self.sensor.position() {
+            self.last_travel
+                .set(position.as_revolutions() * self.gearing.unwrap_or(1.0) * wheel_circumference);
+        }
+
+        self.last_travel.get()
     }
 }
 
+/// Determines which sensors are used to measure a robot's heading.
+///
+/// If the selected source is unavailable (no gyro was provided, or it failed to return a reading),
+/// tracking will fall back to the other source rather than returning an invalid heading.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum HeadingSource {
+    /// Only use the gyro. If a reading fails, the last successful gyro reading is held.
+    GyroOnly,
+
+    /// Only use the difference in travel between the parallel tracking wheels.
+    WheelsOnly,
+
+    /// Use the gyro, falling back to the tracking wheels if a reading fails.
+    #[default]
+    GyroPreferred,
+
+    /// Fuse the gyro and tracking wheel headings.
+    ///
+    /// The fused heading is `wheels + gyro_weight * (gyro - wheels)`, where the difference is taken
+    /// along the shortest arc between the two readings. A `gyro_weight` of `1.0` uses only the gyro,
+    /// and `0.0` uses only the wheels. If the gyro fails, the wheel heading is used.
+    Blend { gyro_weight: f64 },
+}
+
 #[derive(Debug, PartialEq)]
 pub struct ParallelWheelTracking<T: RotarySensor, U: RotarySensor> {
     position: Vec2,
     left_wheel: TrackingWheel<T>,
     right_wheel: TrackingWheel<U>,
     gyro: Option<InertialSensor>,
+    heading_source: HeadingSource,
+    last_gyro_heading: Cell<Option<f64>>,
     heading_offset: f64,
     prev_forward_travel: f64,
     prev_heading: f64,
+    total_rotation: f64,
 }
 
 impl<T: RotarySensor, U: RotarySensor> ParallelWheelTracking<T, U> {
@@ -79,22 +232,195 @@ impl<T: RotarySensor, U: RotarySensor> ParallelWheelTracking<T, U> {
         right_wheel: TrackingWheel<U>,
         gyro: Option<InertialSensor>,
     ) -> Self {
-        Self {
+        let mut tracking = Self {
             position: origin,
             left_wheel,
             right_wheel,
             gyro,
+            heading_source: HeadingSource::default(),
+            last_gyro_heading: Cell::new(None),
             heading_offset: heading,
             prev_forward_travel: 0.0,
             prev_heading: 0.0,
+            total_rotation: 0.0,
+        };
+
+        // Sensors may not read zero at construction, so the first update measures motion from whatever
+        // they currently read rather than treating those readings as the robot having moved.
+        tracking.prev_forward_travel = tracking.forward_travel();
+        tracking.prev_heading = tracking.heading();
+
+        tracking
+    }
+
+    /// Create a [`ParallelWheelTrackingBuilder`] for constructing tracking with named parameters.
+    ///
+    /// The robot's origin and heading default to zero, no gyro is used, and the [`HeadingSource`]
+    /// defaults to [`HeadingSource::GyroPreferred`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// let tracking = ParallelWheelTracking::builder(
+    ///     TrackingWheel::new(left_motors.clone(), 3.25, 7.5, Some(36.0 / 60.0)),
+    ///     TrackingWheel::new(right_motors.clone(), 3.25, 7.5, Some(36.0 / 60.0)),
+    /// )
+    /// .origin(Vec2::new(24.0, 24.0))
+    /// .heading(core::f64::consts::FRAC_PI_2)
+    /// .gyro(InertialSensor::new(peripherals.port_9))
+    /// .build();
+    /// ```
+    pub fn builder(
+        left_wheel: TrackingWheel<T>,
+        right_wheel: TrackingWheel<U>,
+    ) -> ParallelWheelTrackingBuilder<T, U> {
+        ParallelWheelTrackingBuilder {
+            origin: Vec2::default(),
+            heading: 0.0,
+            left_wheel,
+            right_wheel,
+            gyro: None,
+            heading_source: HeadingSource::default(),
         }
     }
+
+    /// Set which sensors are used to measure heading.
+    pub fn with_heading_source(mut self, heading_source: HeadingSource) -> Self {
+        self.heading_source = heading_source;
+        // A different source may read a different heading, which shouldn't count as the robot turning.
+        self.prev_heading = self.heading();
+        self
+    }
+
+    /// Get the policy used for selecting heading sensors.
+    pub fn heading_source(&self) -> HeadingSource {
+        self.heading_source
+    }
+
+    /// Get the total angle the robot has turned through since tracking started, in radians.
+    ///
+    /// Unlike [`Tracking::heading`], which wraps around from -π to +π, this accumulates the change in
+    /// heading from every update without wrapping, so three full counterclockwise turns read as 6π. This
+    /// is useful for counting turns. Calls to [`Tracking::set_heading`] don't count as turning.
+    pub fn total_rotation(&self) -> f64 {
+        self.total_rotation
+    }
+
+    /// Get the distance the left tracking wheel has traveled.
+    pub fn left_travel(&self) -> f64 {
+        self.left_wheel.travel()
+    }
+
+    /// Get the distance the right tracking wheel has traveled.
+    pub fn right_travel(&self) -> f64 {
+        self.right_wheel.travel()
+    }
+}
+
+/// A builder for constructing [`ParallelWheelTracking`], created through [`ParallelWheelTracking::builder`].
+#[derive(Debug, PartialEq)]
+pub struct ParallelWheelTrackingBuilder<T: RotarySensor, U: RotarySensor> {
+    origin: Vec2,
+    heading: f64,
+    left_wheel: TrackingWheel<T>,
+    right_wheel: TrackingWheel<U>,
+    gyro: Option<InertialSensor>,
+    heading_source: HeadingSource,
+}
+
+impl<T: RotarySensor, U: RotarySensor> ParallelWheelTrackingBuilder<T, U> {
+    /// Set the robot's starting position.
+    pub fn origin(mut self, origin: Vec2) -> Self {
+        self.origin = origin;
+        self
+    }
+
+    /// Set the robot's starting heading in radians.
+    pub fn heading(mut self, heading: f64) -> Self {
+        self.heading = heading;
+        self
+    }
+
+    /// Set the gyro used for measuring heading.
+    pub fn gyro(mut self, gyro: InertialSensor) -> Self {
+        self.gyro = Some(gyro);
+        self
+    }
+
+    /// Set which sensors are used to measure heading.
+    pub fn heading_source(mut self, heading_source: HeadingSource) -> Self {
+        self.heading_source = heading_source;
+        self
+    }
+
+    /// Construct the [`ParallelWheelTracking`].
+    pub fn build(self) -> ParallelWheelTracking<T, U> {
+        ParallelWheelTracking::new(
+            self.origin,
+            self.heading,
+            self.left_wheel,
+            self.right_wheel,
+            self.gyro,
+        )
+        .with_heading_source(self.heading_source)
+    }
 }
 
 impl<T: RotarySensor, U: RotarySensor> ParallelWheelTracking<T, U> {
     fn track_width(&self) -> f64 {
         self.left_wheel.offset + self.right_wheel.offset
     }
+
+    /// Compute the robot's heading according to its [`HeadingSource`], without any offset applied.
+    fn raw_heading(&self) -> f64 {
+        let wheel_heading =
+            (self.right_wheel.travel() - self.left_wheel.travel()) / self.track_width();
+        let gyro_heading = self
+            .gyro
+            .as_ref()
+            .and_then(|gyro| gyro.heading().ok())
+            .map(gyro_heading_to_radians);
+
+        if gyro_heading.is_some() {
+            self.last_gyro_heading.set(gyro_heading);
+        }
+
+        fuse_heading(
+            self.heading_source,
+            gyro_heading,
+            self.last_gyro_heading.get(),
+            wheel_heading,
+        )
+    }
+}
+
+/// Convert a heading reported by an [`InertialSensor`] to the convention used by tracking.
+///
+/// vexide reports gyro headings clockwise in degrees, while tracking measures headings counterclockwise in
+/// radians from -π to +π (matching the heading derived from the tracking wheels).
+fn gyro_heading_to_radians(heading: f64) -> f64 {
+    math::normalize_angle(-heading.to_radians())
+}
+
+/// Select or combine the gyro and wheel headings according to a [`HeadingSource`].
+///
+/// `gyro_heading` is the current gyro reading (if it succeeded), and `last_gyro_heading` is the most
+/// recent successful one.
+fn fuse_heading(
+    source: HeadingSource,
+    gyro_heading: Option<f64>,
+    last_gyro_heading: Option<f64>,
+    wheel_heading: f64,
+) -> f64 {
+    match (source, gyro_heading) {
+        (HeadingSource::WheelsOnly, _) => wheel_heading,
+        (HeadingSource::GyroOnly, _) => last_gyro_heading.unwrap_or(wheel_heading),
+        (HeadingSource::GyroPreferred, Some(gyro_heading)) => gyro_heading,
+        (HeadingSource::Blend { gyro_weight }, Some(gyro_heading)) => {
+            wheel_heading + gyro_weight * math::normalize_angle(gyro_heading - wheel_heading)
+        }
+        (_, None) => wheel_heading,
+    }
 }
 
 impl<T: RotarySensor, U: RotarySensor> Tracking for ParallelWheelTracking<T, U> {
@@ -102,8 +428,13 @@ impl<T: RotarySensor, U: RotarySensor> Tracking for ParallelWheelTracking<T, U>
         self.position
     }
 
+    /// Set the robot's position.
+    ///
+    /// Non-finite positions (containing NaN or infinity) are ignored, and the previous position is kept.
     fn set_position(&mut self, position: Vec2) {
-        self.position = position;
+        if position.is_finite() {
+            self.position = position;
+        }
     }
 
     fn forward_travel(&self) -> f64 {
@@ -111,43 +442,767 @@ impl<T: RotarySensor, U: RotarySensor> Tracking for ParallelWheelTracking<T, U>
     }
 
     fn heading(&self) -> f64 {
-        self.heading_offset
-            + if let Some(gyro) = &self.gyro {
-                if let Ok(heading) = gyro.heading() {
-                    heading.to_radians()
-                } else {
-                    (self.right_wheel.travel() - self.left_wheel.travel()) / self.track_width()
-                }
-            } else {
-                (self.right_wheel.travel() - self.left_wheel.travel()) / self.track_width()
-            } % FRAC_2_PI
+        math::normalize_angle(self.heading_offset + self.raw_heading())
+    }
+
+    /// Set the robot's heading.
+    ///
+    /// Non-finite headings (NaN or infinity) are ignored, and the previous heading is kept.
+    fn set_heading(&mut self, heading: f64) {
+        if heading.is_finite() {
+            self.heading_offset = heading - self.raw_heading();
+
+            // Without this, the next update would see the change in offset as the robot turning.
+            self.prev_heading = self.heading();
+        }
+    }
+
+    fn update(&mut self) -> TrackingContext {
+        let prev = TrackingContext {
+            position: self.position,
+            heading: self.prev_heading,
+            forward_travel: self.prev_forward_travel,
+        };
+        let ctx = step(prev, self.forward_travel(), self.heading());
+
+        // A single non-finite sensor reading would otherwise poison every future position estimate,
+        // so glitched updates are discarded and the last good state is held.
+        if !(ctx.position.is_finite() && ctx.heading.is_finite() && ctx.forward_travel.is_finite())
+        {
+            return prev;
+        }
+
+        self.position = ctx.position;
+        self.prev_forward_travel = ctx.forward_travel;
+        self.total_rotation += math::normalize_angle(ctx.heading - prev.heading);
+        self.prev_heading = ctx.heading;
+
+        ctx
+    }
+}
+
+/// Corrects a tracking system whose reported pose isn't at the robot's center of rotation.
+///
+/// `OffsetTracking` wraps another [`Tracking`] implementation, and transforms its reported pose so that it
+/// corresponds to the robot's true center of rotation.
+///
+/// - `offset` is the position of the point reported by the inner tracking system, relative to the robot's
+///   center of rotation. It is measured in the robot's frame of reference, where +x points forwards when the
+///   robot's heading is zero, and is rotated by the robot's heading before being applied.
+/// - `heading_offset` is added to the inner tracking system's heading, in radians. The result is wrapped to
+///   the range [-π, π), like any other heading.
+#[derive(Debug, PartialEq)]
+pub struct OffsetTracking<T: Tracking> {
+    pub inner: T,
+    pub offset: Vec2,
+    pub heading_offset: f64,
+}
+
+impl<T: Tracking> OffsetTracking<T> {
+    /// Construct a new [`OffsetTracking`] from an inner tracking system and mounting offsets.
+    pub fn new(inner: T, offset: Vec2, heading_offset: f64) -> Self {
+        Self {
+            inner,
+            offset,
+            heading_offset,
+        }
+    }
+}
+
+impl<T: Tracking> Tracking for OffsetTracking<T> {
+    fn forward_travel(&self) -> f64 {
+        self.inner.forward_travel()
+    }
+
+    fn heading(&self) -> f64 {
+        math::normalize_angle(self.inner.heading() + self.heading_offset)
     }
 
     fn set_heading(&mut self, heading: f64) {
-        self.heading_offset = heading - self.heading();
+        self.inner
+            .set_heading(math::normalize_angle(heading - self.heading_offset));
+    }
+
+    fn position(&self) -> Vec2 {
+        self.inner.position() - self.offset.rotate(self.heading())
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        self.inner
+            .set_position(position + self.offset.rotate(self.heading()));
     }
 
     fn update(&mut self) -> TrackingContext {
-        let forward_travel = self.forward_travel();
-        let heading = self.heading();
-
-        let delta_forward_travel = forward_travel - self.prev_forward_travel;
-        let delta_heading = heading - self.prev_heading;
-
-        // Find a position delta.
-        // This is a vector relative to the previous position, and can be found by creating a vector with our
-        // average forward travel as the y-axis, then rotating the y-axis about our current heading. This gives
-        // a rough estimate of the change in position, but does not account for sideways motion.
-        self.position += Vec2::from_polar(
-            2.0 * (delta_forward_travel / delta_heading) * (heading / 2.0).sin(),
-            (self.prev_heading + delta_heading) / 2.0,
+        let mut ctx = self.inner.update();
+
+        ctx.heading = math::normalize_angle(ctx.heading + self.heading_offset);
+        ctx.position -= self.offset.rotate(ctx.heading);
+
+        ctx
+    }
+}
+
+/// A tracking system that replays a pre-recorded sequence of tracking samples.
+///
+/// Each call to [`Tracking::update`] advances to the next sample in the sequence. Once every sample has
+/// been replayed, the final sample is repeated indefinitely. This allows commands to be run against a
+/// known, deterministic sequence of tracking states.
+///
+/// Samples are stored as [`TrackingContext`]s, which can be recorded from any other tracking system by
+/// saving the return value of its `update` method. Calling [`Tracking::set_position`] or
+/// [`Tracking::set_heading`] only affects the current sample, and will be overwritten by the next update.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct ReplayTracking {
+    samples: Vec<TrackingContext>,
+    index: usize,
+    current: TrackingContext,
+}
+
+impl ReplayTracking {
+    /// Construct a new [`ReplayTracking`] from a sequence of recorded samples.
+    pub fn new(samples: impl Into<Vec<TrackingContext>>) -> Self {
+        let samples = samples.into();
+
+        Self {
+            current: samples.first().copied().unwrap_or_default(),
+            samples,
+            index: 0,
+        }
+    }
+
+    /// Returns `true` if every sample has been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.index >= self.samples.len()
+    }
+}
+
+impl Tracking for ReplayTracking {
+    fn forward_travel(&self) -> f64 {
+        self.current.forward_travel
+    }
+
+    fn heading(&self) -> f64 {
+        self.current.heading
+    }
+
+    fn set_heading(&mut self, heading: f64) {
+        self.current.heading = heading;
+    }
+
+    fn position(&self) -> Vec2 {
+        self.current.position
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        self.current.position = position;
+    }
+
+    fn update(&mut self) -> TrackingContext {
+        if let Some(sample) = self.samples.get(self.index) {
+            self.current = *sample;
+            self.index += 1;
+        }
+
+        self.current
+    }
+}
+
+/// A robot's velocity at a sample recorded by a [`PathRecorder`].
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct SampleVelocity {
+    /// Velocity along the robot's heading, in distance units per second. Negative when driving backwards.
+    pub linear: f64,
+
+    /// Angular velocity in radians per second. Positive when turning counterclockwise.
+    pub angular: f64,
+}
+
+/// Records the poses reported by another tracking system.
+///
+/// `PathRecorder` wraps another [`Tracking`] implementation, passing through all of its readings. While
+/// recording, every `interval`th call to [`Tracking::update`] saves the returned [`TrackingContext`]. This
+/// allows a path to be recorded by driving the robot by hand, then exported and replayed later using
+/// [`ReplayTracking`].
+///
+/// A drivetrain updates its tracking system once every [`Motor::DATA_READ_INTERVAL`] (10 milliseconds),
+/// so an `interval` of `1` records 100 samples per second. Each sample takes 32 bytes, so a one minute
+/// recording at this rate uses roughly 190 kilobytes. Larger intervals trade path resolution for memory.
+///
+/// Velocities aren't stored, but can be estimated from the change in pose between consecutive samples
+/// using [`PathRecorder::velocities`].
+#[derive(Debug, PartialEq)]
+pub struct PathRecorder<T: Tracking> {
+    pub inner: T,
+    samples: Vec<TrackingContext>,
+    interval: usize,
+    updates: usize,
+    recording: bool,
+}
+
+impl<T: Tracking> PathRecorder<T> {
+    /// Construct a new [`PathRecorder`] from an inner tracking system, recording one sample every
+    /// `interval` updates.
+    ///
+    /// The recorder doesn't record anything until [`PathRecorder::start`] is called.
+    pub fn new(inner: T, interval: usize) -> Self {
+        Self {
+            inner,
+            samples: Vec::new(),
+            interval: interval.max(1),
+            updates: 0,
+            recording: false,
+        }
+    }
+
+    /// Begin recording samples, discarding any previously recorded ones.
+    pub fn start(&mut self) {
+        self.samples.clear();
+        self.updates = 0;
+        self.recording = true;
+    }
+
+    /// Stop recording samples.
+    ///
+    /// Samples recorded so far are kept until the next call to [`PathRecorder::start`].
+    pub fn stop(&mut self) {
+        self.recording = false;
+    }
+
+    /// Returns `true` if the recorder is currently recording.
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Get the samples recorded so far.
+    pub fn samples(&self) -> &[TrackingContext] {
+        &self.samples
+    }
+
+    /// Get the time between consecutive recorded samples.
+    ///
+    /// This assumes the recorder is updated once every [`Motor::DATA_READ_INTERVAL`], as it is when used
+    /// as a drivetrain's tracking system.
+    pub fn sample_period(&self) -> Duration {
+        Motor::DATA_READ_INTERVAL * self.interval as u32
+    }
+
+    /// Estimate the robot's velocity at each recorded sample from the change in pose since the previous
+    /// sample.
+    ///
+    /// The returned velocities line up with [`PathRecorder::samples`]. The first sample has no previous
+    /// sample to compare against, so its velocity is zero.
+    pub fn velocities(&self) -> Vec<SampleVelocity> {
+        let dt = self.sample_period().as_secs_f64();
+
+        let mut velocities = Vec::with_capacity(self.samples.len());
+        if !self.samples.is_empty() {
+            velocities.push(SampleVelocity::default());
+        }
+
+        velocities.extend(self.samples.windows(2).map(|window| {
+            let (prev, ctx) = (window[0], window[1]);
+            let delta_heading = math::normalize_angle(ctx.heading - prev.heading);
+
+            // Over a circular arc, the chord between two poses points along the average of their headings,
+            // so projecting the change in position onto that direction gives a signed distance.
+            let (sin, cos) = math::sin_cos(prev.heading + delta_heading / 2.0);
+            let distance = Vec2::new(cos, sin).dot(ctx.position - prev.position);
+
+            SampleVelocity {
+                linear: distance / dt,
+                angular: delta_heading / dt,
+            }
+        }));
+
+        velocities
+    }
+
+    /// Export the recorded samples into a [`ReplayTracking`] for playback.
+    pub fn export(&self) -> ReplayTracking {
+        ReplayTracking::new(self.samples.clone())
+    }
+}
+
+impl<T: Tracking> Tracking for PathRecorder<T> {
+    fn forward_travel(&self) -> f64 {
+        self.inner.forward_travel()
+    }
+
+    fn heading(&self) -> f64 {
+        self.inner.heading()
+    }
+
+    fn set_heading(&mut self, heading: f64) {
+        self.inner.set_heading(heading);
+    }
+
+    fn position(&self) -> Vec2 {
+        self.inner.position()
+    }
+
+    fn set_position(&mut self, position: Vec2) {
+        self.inner.set_position(position);
+    }
+
+    fn update(&mut self) -> TrackingContext {
+        let ctx = self.inner.update();
+
+        if self.recording {
+            if self.updates.is_multiple_of(self.interval) {
+                self.samples.push(ctx);
+            }
+            self.updates += 1;
+        }
+
+        ctx
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::f64::consts::FRAC_PI_2;
+
+    use vexide::devices::position::Position;
+
+    use super::*;
+
+    /// A rotary sensor whose reading can be set by the test.
+    #[derive(Default, Debug, PartialEq)]
+    struct MockSensor(Cell<Position>);
+
+    impl RotarySensor for MockSensor {
+        type Error = ();
+
+        fn position(&self) -> Result<Position, Self::Error> {
+            Ok(self.0.get())
+        }
+    }
+
+    /// Loose enough to also hold with the `fast-math` trigonometric approximations.
+    const EPSILON: f64 = 1e-4;
+
+    fn assert_close(actual: Vec2, expected: Vec2) {
+        assert!(
+            (actual - expected).length() < EPSILON,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn step_without_turning_is_finite() {
+        let prev = TrackingContext::default();
+
+        assert_eq!(step(prev, 0.0, 0.0), prev);
+
+        let ctx = step(prev, 2.0, 0.0);
+        assert!(ctx.position.is_finite());
+        assert_close(ctx.position, Vec2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn step_straight() {
+        let prev = TrackingContext {
+            position: Vec2::new(1.0, 1.0),
+            heading: FRAC_PI_2,
+            forward_travel: 3.0,
+        };
+        let ctx = step(prev, 8.0, FRAC_PI_2);
+
+        assert_close(ctx.position, Vec2::new(1.0, 6.0));
+        assert_eq!(ctx.forward_travel, 8.0);
+    }
+
+    #[test]
+    fn step_pure_rotation() {
+        let prev = TrackingContext {
+            position: Vec2::new(1.0, 1.0),
+            ..Default::default()
+        };
+        let ctx = step(prev, 0.0, 1.0);
+
+        assert_eq!(ctx.position, prev.position);
+        assert_eq!(ctx.heading, 1.0);
+    }
+
+    #[test]
+    fn step_arc() {
+        // A counterclockwise quarter circle with a radius of 10 ends up 10 units forwards and to the left.
+        let radius = 10.0;
+        let mut ctx = TrackingContext::default();
+
+        for i in 1..=10 {
+            let t = i as f64 / 10.0;
+            ctx = step(ctx, radius * FRAC_PI_2 * t, FRAC_PI_2 * t);
+        }
+
+        assert_close(ctx.position, Vec2::new(radius, radius));
+    }
+
+    #[test]
+    fn try_new_rejects_invalid_measurements() {
+        assert_eq!(
+            TrackingWheel::try_new(MockSensor::default(), 0.0, 0.0, None).unwrap_err(),
+            TrackingWheelError::InvalidDiameter(0.0)
+        );
+        assert_eq!(
+            TrackingWheel::try_new(MockSensor::default(), -2.75, 0.0, None).unwrap_err(),
+            TrackingWheelError::InvalidDiameter(-2.75)
+        );
+        assert_eq!(
+            TrackingWheel::try_new(MockSensor::default(), 2.75, 0.0, Some(0.0)).unwrap_err(),
+            TrackingWheelError::InvalidGearing(0.0)
+        );
+        assert!(TrackingWheel::try_new(MockSensor::default(), 2.75, 0.0, Some(0.6)).is_ok());
+    }
+
+    #[test]
+    fn gyro_heading_is_counterclockwise() {
+        assert!((gyro_heading_to_radians(90.0) + FRAC_PI_2).abs() < 1e-12);
+        assert!((gyro_heading_to_radians(270.0) - FRAC_PI_2).abs() < 1e-12);
+    }
+
+    #[test]
+    fn heading_sources_select_expected_readings() {
+        let wheels = 0.5;
+
+        assert_eq!(
+            fuse_heading(HeadingSource::WheelsOnly, Some(1.0), Some(1.0), wheels),
+            wheels
+        );
+
+        assert_eq!(
+            fuse_heading(HeadingSource::GyroOnly, Some(1.0), Some(1.0), wheels),
+            1.0
+        );
+        assert_eq!(
+            fuse_heading(HeadingSource::GyroOnly, None, Some(0.8), wheels),
+            0.8
+        );
+        assert_eq!(
+            fuse_heading(HeadingSource::GyroOnly, None, None, wheels),
+            wheels
+        );
+
+        assert_eq!(
+            fuse_heading(HeadingSource::GyroPreferred, Some(1.0), Some(1.0), wheels),
+            1.0
         );
-        self.prev_forward_travel = forward_travel;
+        assert_eq!(
+            fuse_heading(HeadingSource::GyroPreferred, None, Some(0.8), wheels),
+            wheels
+        );
+
+        let blend = HeadingSource::Blend { gyro_weight: 0.25 };
+        assert!((fuse_heading(blend, Some(1.0), Some(1.0), wheels) - 0.625).abs() < 1e-12);
+        assert_eq!(fuse_heading(blend, None, Some(1.0), wheels), wheels);
+
+        // Blending across the ±π boundary should stay near π rather than averaging to zero.
+        let blend = HeadingSource::Blend { gyro_weight: 0.5 };
+        let fused = fuse_heading(blend, Some(-PI + 0.1), None, PI - 0.1);
+        assert!((math::normalize_angle(fused) + PI).abs() < 1e-12);
+    }
+
+    #[test]
+    fn step_takes_shortest_arc_across_wraparound() {
+        let prev = TrackingContext {
+            heading: PI - 0.05,
+            ..Default::default()
+        };
+        let ctx = step(prev, 0.1, -PI + 0.05);
+
+        // The robot turned 0.1 rad through π while driving backwards along the x-axis.
+        assert_close(ctx.position, Vec2::new(-0.1, 0.0));
+    }
+
+    #[test]
+    fn set_pose_overrides_tracked_pose() {
+        let left = TrackingWheel::new(MockSensor::default(), 2.0, 5.0, None);
+        let right = TrackingWheel::new(MockSensor::default(), 2.0, 5.0, None);
+
+        // Turn the robot in place, so the wheels report a nonzero heading.
+        left.sensor.0.set(Position::from_revolutions(-0.5));
+        right.sensor.0.set(Position::from_revolutions(0.5));
+
+        let mut tracking =
+            ParallelWheelTracking::new(Vec2::default(), FRAC_PI_2, left, right, None);
+        tracking.update();
+
+        tracking.set_position(Vec2::new(10.0, 20.0));
+        tracking.set_heading(0.25);
+
+        assert_eq!(tracking.position(), Vec2::new(10.0, 20.0));
+        assert!((tracking.heading() - 0.25).abs() < 1e-12);
+
+        let ctx = tracking.update();
+        assert_eq!(ctx.position, Vec2::new(10.0, 20.0));
+        assert!((ctx.heading - 0.25).abs() < 1e-12);
+    }
+
+    #[test]
+    fn total_rotation_accumulates_across_wraps() {
+        // A circumference of 1 makes each revolution of a sensor one unit of travel.
+        let left = TrackingWheel::new(MockSensor::default(), 1.0 / PI, 5.0, None);
+        let right = TrackingWheel::new(MockSensor::default(), 1.0 / PI, 5.0, None);
+        let mut tracking = ParallelWheelTracking::new(Vec2::default(), 0.0, left, right, None);
+
+        // Turn three full times counterclockwise in place.
+        for i in 1..=60 {
+            let heading = 6.0 * PI * i as f64 / 60.0;
+
+            tracking
+                .left_wheel
+                .sensor
+                .0
+                .set(Position::from_revolutions(-heading * 5.0));
+            tracking
+                .right_wheel
+                .sensor
+                .0
+                .set(Position::from_revolutions(heading * 5.0));
+            tracking.update();
+
+            assert!(tracking.heading() >= -PI && tracking.heading() < PI);
+        }
+
+        assert!((tracking.total_rotation() - 6.0 * PI).abs() < 1e-4);
+    }
+
+    #[test]
+    fn offset_tracking_cancels_orbit_of_offset_point() {
+        let center = Vec2::new(3.0, -2.0);
+        let offset = Vec2::new(4.0, 1.0);
+
+        // Rotating in place about `center` makes the tracked point orbit around it.
+        let samples: Vec<TrackingContext> = (0..16)
+            .map(|i| {
+                let heading = math::normalize_angle(i as f64 * 0.5);
+
+                TrackingContext {
+                    position: center + offset.rotate(heading),
+                    heading,
+                    forward_travel: 0.0,
+                }
+            })
+            .collect();
+        let mut tracking = OffsetTracking::new(ReplayTracking::new(samples.clone()), offset, 0.0);
+
+        for sample in samples {
+            let ctx = tracking.update();
+
+            assert_close(ctx.position, center);
+            assert_close(tracking.position(), center);
+            assert_eq!(ctx.heading, sample.heading);
+        }
+    }
+
+    #[test]
+    fn path_recorder_samples_every_interval() {
+        let samples: Vec<TrackingContext> = (0..10)
+            .map(|i| TrackingContext {
+                position: Vec2::new(i as f64, 0.0),
+                heading: 0.0,
+                forward_travel: i as f64,
+            })
+            .collect();
+        let mut recorder = PathRecorder::new(ReplayTracking::new(samples.clone()), 2);
+
+        // Updates before recording starts aren't saved.
+        recorder.update();
+        recorder.start();
+        for _ in 0..6 {
+            recorder.update();
+        }
+        recorder.stop();
+        recorder.update();
+
+        assert!(!recorder.is_recording());
+        assert_eq!(recorder.samples(), &[samples[1], samples[3], samples[5]],);
+
+        let mut replay = recorder.export();
+        for sample in [samples[1], samples[3], samples[5]] {
+            assert_eq!(replay.update(), sample);
+        }
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn non_finite_pose_is_rejected() {
+        let left = TrackingWheel::new(MockSensor::default(), 2.0, 5.0, None);
+        let right = TrackingWheel::new(MockSensor::default(), 2.0, 5.0, None);
+        let mut tracking = ParallelWheelTracking::new(Vec2::new(1.0, 2.0), 0.5, left, right, None);
+
+        tracking.set_position(Vec2::new(f64::NAN, 0.0));
+        tracking.set_heading(f64::INFINITY);
+
+        assert_eq!(tracking.position(), Vec2::new(1.0, 2.0));
+        assert!((tracking.heading() - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn replayed_straight_drive_settles_command() {
+        use core::{ops::ControlFlow, time::Duration};
+
+        use crate::{
+            commands::{closure::ClosureCommand, Command},
+            drivetrain::Voltages,
+        };
+
+        const TARGET: f64 = 24.0;
+
+        // A robot driving straight forward 24 inches over 50 updates.
+        let samples: Vec<TrackingContext> = (0..=50)
+            .map(|i| TrackingContext {
+                position: Vec2::new(0.0, TARGET * i as f64 / 50.0),
+                heading: FRAC_PI_2,
+                forward_travel: TARGET * i as f64 / 50.0,
+            })
+            .collect();
+        let mut tracking = ReplayTracking::new(samples);
+
+        let mut command = ClosureCommand::new(|ctx: TrackingContext, _dt: Duration| {
+            let error = TARGET - ctx.forward_travel;
+
+            if error.abs() < 0.5 {
+                ControlFlow::Break(())
+            } else {
+                ControlFlow::Continue(Voltages(error, error))
+            }
+        });
+
+        let mut updates = 0;
+        while !command.is_settled() {
+            assert!(!tracking.is_finished(), "command never settled");
+
+            let Voltages(left, right) = command.update(tracking.update());
+            assert_eq!(left, right);
+            updates += 1;
+        }
+
+        assert_eq!(updates, 50);
+        assert_eq!(command.update(tracking.update()), Voltages::default());
+    }
+
+    #[test]
+    fn stationary_robot_does_not_move_on_first_update() {
+        let left = TrackingWheel::new(MockSensor::default(), 2.0, 5.0, None);
+        let right = TrackingWheel::new(MockSensor::default(), 2.0, 5.0, None);
+
+        // The wheels have already turned before tracking is constructed.
+        left.sensor.0.set(Position::from_revolutions(1.0));
+        right.sensor.0.set(Position::from_revolutions(1.5));
+
+        let mut tracking = ParallelWheelTracking::new(Vec2::new(1.0, 2.0), 1.5, left, right, None);
+        let heading = tracking.heading();
+        let ctx = tracking.update();
+
+        assert_eq!(tracking.total_rotation(), 0.0);
+        assert_eq!(ctx.position, Vec2::new(1.0, 2.0));
+        assert_eq!(ctx.heading, heading);
+    }
+
+    #[test]
+    fn locked_shared_sensor_holds_last_travel() {
+        use alloc::sync::Arc;
+        use vexide::core::sync::Mutex;
+
+        use crate::devices::SharedSensorError;
+
+        // A circumference of 1 makes each revolution of the sensor one unit of travel.
+        let sensor = Arc::new(Mutex::new(MockSensor::default()));
+        let wheel = TrackingWheel::new(Arc::clone(&sensor), 1.0 / PI, 5.0, None);
+
+        sensor
+            .try_lock()
+            .unwrap()
+            .0
+            .set(Position::from_revolutions(2.0));
+        assert!((wheel.travel() - 2.0).abs() < 1e-12);
+
+        // Another task (such as the drivetrain writing voltages) is holding the lock while the wheel is read.
+        let guard = sensor.try_lock().unwrap();
+        guard.0.set(Position::from_revolutions(3.0));
+
+        assert_eq!(wheel.sensor.position(), Err(SharedSensorError::Busy));
+        assert!((wheel.travel() - 2.0).abs() < 1e-12);
+
+        drop(guard);
+        assert!((wheel.travel() - 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn track_width_from_known_spin() {
+        // Two counterclockwise turns with 12 inch wide wheels drive each wheel 2 * 2π * 6 inches.
+        let rotation = 4.0 * PI;
+        let travel = rotation * 6.0;
+
+        assert!((track_width_from_spin(-travel, travel, rotation) - 12.0).abs() < 1e-12);
+        assert!((track_width_from_spin(travel, -travel, -rotation) - 12.0).abs() < 1e-12);
+
+        // Scrub makes the wheels travel less than they would with an ideal 12 inch track width.
+        assert!(
+            (track_width_from_spin(-0.9 * travel, 0.9 * travel, rotation) - 10.8).abs() < 1e-12
+        );
+
+        assert!(track_width_from_spin(1.0, 1.0, 0.0).is_nan());
+    }
+
+    #[test]
+    fn offset_tracking_wraps_heading() {
+        let sample = TrackingContext {
+            position: Vec2::new(1.0, 1.0),
+            heading: 3.0,
+            forward_travel: 0.0,
+        };
+        let mut tracking =
+            OffsetTracking::new(ReplayTracking::new([sample]), Vec2::new(2.0, 0.0), 1.0);
+        let expected = 4.0 - 2.0 * PI;
+
+        let ctx = tracking.update();
+        assert!((ctx.heading - expected).abs() < 1e-12);
+        assert!((tracking.heading() - expected).abs() < 1e-12);
+        assert_close(
+            ctx.position,
+            Vec2::new(1.0, 1.0) - Vec2::new(2.0, 0.0).rotate(4.0),
+        );
+
+        // Setting the heading across the boundary keeps the inner heading wrapped as well.
+        tracking.set_heading(-3.0);
+        assert!((tracking.inner.heading() - (2.0 * PI - 4.0)).abs() < 1e-12);
+        assert!((tracking.heading() + 3.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn path_recorder_estimates_velocities() {
+        // Backing up around a circle with a radius of 10, turning clockwise by 0.02 rad per update.
+        let samples: Vec<TrackingContext> = (0..10)
+            .map(|i| {
+                let angle = -0.02 * i as f64;
+
+                TrackingContext {
+                    position: Vec2::new(10.0 * angle.sin(), 10.0 - 10.0 * angle.cos()),
+                    heading: angle,
+                    forward_travel: 10.0 * angle,
+                }
+            })
+            .collect();
+        let mut recorder = PathRecorder::new(ReplayTracking::new(samples), 2);
+
+        recorder.start();
+        for _ in 0..10 {
+            recorder.update();
+        }
+
+        assert_eq!(recorder.sample_period(), Duration::from_millis(20));
+
+        let velocities = recorder.velocities();
+        assert_eq!(velocities.len(), recorder.samples().len());
+        assert_eq!(velocities[0], SampleVelocity::default());
 
-        TrackingContext {
-            position: self.position(),
-            heading: self.heading(),
-            forward_travel: self.forward_travel(),
+        // Each recorded sample is 0.04 rad (a chord of slightly under 0.4 inches) from the last, 20ms apart.
+        let chord = 20.0 * (0.02_f64).sin();
+        for velocity in &velocities[1..] {
+            assert!((velocity.linear + chord / 0.02).abs() < EPSILON);
+            assert!((velocity.angular + 2.0).abs() < EPSILON);
         }
     }
 }